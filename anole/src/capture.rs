@@ -1,4 +1,6 @@
 
+use crate::{store::Store, value::{self, Value}};
+
 /// 捕获参数
 /// 
 #[derive(Debug)]
@@ -8,6 +10,8 @@ pub enum Capture<'a> {
     Json(Cap<'a>),
     Xml(Cap<'a>),
     Column(Cap<'a>),
+    /// Negotiated HTTP protocol version, e.g. `HTTP/2.0`.
+    Version(Cap<'a>),
 }
 
 impl <'a> Capture<'a> {
@@ -23,9 +27,13 @@ impl <'a> Capture<'a> {
         matches!(self, Self::Xml(_))
     }
 
+    pub(crate) fn is_version(&self) -> bool {
+        matches!(self, Self::Version(_))
+    }
+
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum CapValueType {
     Size,
     I32,
@@ -34,8 +42,34 @@ pub enum CapValueType {
     U64,
     Bool,
     Str,
+    /// Date, captured as `Value::Date`, not supported by the MSSQL driver.
     Date,
+    /// Time of day, captured as `Value::Time`, not supported by the MSSQL driver.
     Time,
+    /// UUID, captured as hyphenated string.
+    Uuid,
+    /// JSON or JSONB, captured as structured value.
+    Json,
+    /// Timestamp or datetime, captured as `Value::DateTime`, datetimes without time zone are in UTC.
+    /// Not supported by the MSSQL driver, `CONVERT` the column to text and capture as `Str` instead.
+    Timestamp,
+    /// Arbitrary precision numeric, captured as `Value::Decimal`.
+    /// Not supported by the MSSQL driver, capture as `Str` or `F64` instead.
+    Decimal,
+    /// Float or double, captured as `Value::F64`.
+    F64,
+    /// Binary or blob, captured as `Value::Bytes`.
+    /// Not supported by the MSSQL driver, `CONVERT(VARCHAR(MAX), col, 2)` captures hex text as `Str` instead.
+    Bytes,
+    /// Array of element type, captured as array value.
+    Array(Box<CapValueType>),
+}
+
+impl CapValueType {
+    /// Array of element type.
+    pub fn array(t: CapValueType) -> Self {
+        CapValueType::Array(Box::new(t))
+    }
 }
 
 #[derive(Debug)]
@@ -45,45 +79,6 @@ pub struct Cap<'a> {
     pub data_type: Option<CapValueType>,
 }
 
-impl <'a> Cap<'a> {
-
-    pub(crate) fn is_usize(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::Size))
-    }
-    
-    pub(crate) fn is_i32(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::I32))
-    }
-
-    pub(crate) fn is_u32(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::U32))
-    }
-
-    pub(crate) fn is_i64(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::I64))
-    }
-
-    pub(crate) fn is_u64(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::U64))
-    }
-
-    pub(crate) fn is_bool(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::Bool))
-    }
-
-    pub(crate) fn is_str(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::Str))
-    }
-
-    pub(crate) fn is_date(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::Date))
-    }
-
-    pub(crate) fn is_time(&self) -> bool {
-        matches!(self.data_type, Some(CapValueType::Time))
-    }
-}
-
 pub fn header<'a>(key: &'a str, save_key: &'a str) -> Capture<'a> {
     Capture::Header(Cap {key, save_key, data_type: None})
 }
@@ -98,4 +93,27 @@ pub fn xml<'a>(key: &'a str, save_key: &'a str) -> Capture<'a> {
 
 pub fn column<'a>(key: &'a str, save_key: &'a str, t: CapValueType) -> Capture<'a> {
     Capture::Column(Cap {key, save_key, data_type: Some(t)})
-}
\ No newline at end of file
+}
+
+/// Column captured as the type inferred from its SQL type.
+pub fn column_auto<'a>(key: &'a str, save_key: &'a str) -> Capture<'a> {
+    Capture::Column(Cap {key, save_key, data_type: None})
+}
+
+pub fn version(save_key: &str) -> Capture<'_> {
+    Capture::Version(Cap {key: "", save_key, data_type: None})
+}
+
+/// Save values of json captures into store, null values are saved as `Value::Null` and missing paths are skipped.
+pub(crate) fn save_json(caps: &[&Capture], json_values: &serde_json::Value, store: &mut Store) {
+    if json_values.is_null() {
+        return;
+    }
+    for _cap in caps {
+        if let Capture::Json(_c) = _cap {
+            if let Some(cv) = value::parse_json_value(json_values, _c.key.to_owned()) {
+                store.set(_c.save_key.to_owned(), Value::from(&cv));
+            }
+        }
+    }
+}
@@ -1,15 +1,58 @@
-use crate::{store::Store, report::{Reporter, ReportItem}};
+use std::collections::HashMap;
+
+use crate::{store::Store, report::{Reporter, ReportItem}, task::{http::{session::{ClientOverride, HttpSession, HttpSessionOption}, auth::{Auth, Token}}, db::{DBClientOption, DBConnection, Driver, client::DBClient}}};
 
 pub struct Context {
     pub store: Store,
     pub reporter: Option<Box<dyn Reporter>>,
+    pub(crate) http_sessions: HashMap<String, HttpSession>,
+    pub(crate) oauth2_tokens: HashMap<String, Token>,
+    pub(crate) db_connections: HashMap<String, DBConnection>,
 }
 
 impl Context {
     pub(crate) fn new() -> Self {
         Context {
             store: Store::new(),
-            reporter: None
+            reporter: None,
+            http_sessions: HashMap::new(),
+            oauth2_tokens: HashMap::new(),
+            db_connections: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn with_http_session(&mut self, name: &str, option: HttpSessionOption) {
+        self.http_sessions.insert(name.to_string(), HttpSession::new(option));
+    }
+
+    /// Returns client of the named session with client options overridden, undeclared session is created with default options.
+    pub(crate) fn http_client(&mut self, name: &str, overrides: &ClientOverride) -> crate::Result<reqwest::Client> {
+        self.http_sessions.entry(name.to_string())
+            .or_insert_with(|| HttpSession::new(HttpSessionOption::default()))
+            .client(overrides)
+    }
+
+    /// Returns auth of the named session.
+    pub(crate) fn http_auth(&self, name: &str) -> Option<Auth> {
+        self.http_sessions.get(name).and_then(|s| s.auth())
+    }
+
+    pub(crate) fn with_db_connection(&mut self, name: &str, options: &DBClientOption<'_>) {
+        self.db_connections.insert(name.to_string(), DBConnection::new(options));
+    }
+
+    /// Returns pool of the named database connection, connected on first use.
+    pub(crate) async fn db_client(&mut self, name: &str, driver: Option<Driver>) -> crate::Result<DBClient> {
+        match self.db_connections.get_mut(name) {
+            Some(c) => c.client(driver).await,
+            None => Err(crate::error::create_client(format!("database connection {} not declared", name).into()))
+        }
+    }
+
+    /// Close pools of all database connections.
+    pub(crate) async fn close_db_connections(&mut self) {
+        for c in self.db_connections.values_mut() {
+            c.close().await;
         }
     }
 
@@ -4,19 +4,24 @@ use log::debug;
 
 use crate::report::Reporter;
 use crate::task::Task;
-use crate::{context::Context, task::http::HttpTask};
-use crate::task::db::mysql::MysqlTask;
+use crate::{context::Context, task::http::{HttpTask, session::HttpSessionOption}};
+use crate::task::db::{script::SqlScript, DBClientOption, DatabaseTask, mysql::MysqlTask, postgres::PostgresTask, sqlite::SqliteTask};
+use crate::task::websocket::WebSocketTask;
+use crate::task::grpc::GrpcTask;
+use crate::task::redis::RedisTask;
 
 /// Manage tasks and store capture values.
 pub struct Engine<'a> {
     ctx: Box<Context>,
     tasks: Vec<Task<'a>>,
+    setups: Vec<SqlScript<'a>>,
+    teardowns: Vec<SqlScript<'a>>,
 }
 
 impl<'a> Engine<'a> {
     /// Create an Engine
     pub fn new() -> Self {
-        Engine { ctx: Box::new(Context::new()), tasks: vec![] }
+        Engine { ctx: Box::new(Context::new()), tasks: vec![], setups: vec![], teardowns: vec![] }
     }
 
     /// Add HTTP task
@@ -25,12 +30,74 @@ impl<'a> Engine<'a> {
         self
     }
 
+    /// Add database task, the driver is selected by url scheme.
+    pub fn with_database(mut self, t: DatabaseTask<'a>) -> Self {
+        self.tasks.push(t.into());
+        self
+    }
+
     /// Add MySQL task
     pub fn with_mysql(mut self, t: MysqlTask<'a>) -> Self {
         self.tasks.push(t.into());
         self
     }
 
+    /// Add PostgreSQL task
+    pub fn with_postgres(mut self, t: PostgresTask<'a>) -> Self {
+        self.tasks.push(t.into());
+        self
+    }
+
+    /// Add SQLite task
+    pub fn with_sqlite(mut self, t: SqliteTask<'a>) -> Self {
+        self.tasks.push(t.into());
+        self
+    }
+
+    /// Add WebSocket task
+    pub fn with_websocket(mut self, t: WebSocketTask<'a>) -> Self {
+        self.tasks.push(t.into());
+        self
+    }
+
+    /// Add gRPC task
+    pub fn with_grpc(mut self, t: GrpcTask<'a>) -> Self {
+        self.tasks.push(t.into());
+        self
+    }
+
+    /// Add Redis task
+    pub fn with_redis(mut self, t: RedisTask<'a>) -> Self {
+        self.tasks.push(t.into());
+        self
+    }
+
+    /// Add SQL script executed before all tasks.
+    pub fn with_setup(mut self, s: SqlScript<'a>) -> Self {
+        self.setups.push(s);
+        self
+    }
+
+    /// Add SQL script executed after all tasks, even if a setup script or a task fails.
+    pub fn with_teardown(mut self, s: SqlScript<'a>) -> Self {
+        self.teardowns.push(s);
+        self
+    }
+
+    /// Declare a named HTTP session, HTTP tasks select it by `HttpTaskBuilder::session`.
+    /// Use `task::http::session::DEFAULT_SESSION` to configure the session used by default.
+    pub fn with_http_session(mut self, name: &str, option: HttpSessionOption) -> Self {
+        self.ctx.with_http_session(name, option);
+        self
+    }
+
+    /// Declare a named database connection, database tasks and scripts select it by `connection`.
+    /// The pool is connected on first use, shared by all tasks selecting it and closed when run ends.
+    pub fn with_database_connection(mut self, name: &str, options: DBClientOption<'_>) -> Self {
+        self.ctx.with_db_connection(name, &options);
+        self
+    }
+
     /// Add Reporter
     pub fn with_reporter(mut self, r: Box<dyn Reporter>) -> Self {
         self.ctx.with_reporter(r);
@@ -39,16 +106,36 @@ impl<'a> Engine<'a> {
 
     /// Start engine 
     pub async fn run(mut self) -> crate::Result<()> {
-        for mut ele in self.tasks.into_iter() {
-            match ele.execute(self.ctx.as_mut()).await {
-                Ok(_) => continue,
-                Err(e) => return Err(e)
-            };
+        let mut r = Ok(());
+        for s in &self.setups {
+            if let Err(e) = s.execute(self.ctx.as_mut()).await {
+                r = Err(e);
+                break;
+            }
+        }
+        if r.is_ok() {
+            for mut ele in self.tasks.into_iter() {
+                if let Err(e) = ele.execute(self.ctx.as_mut()).await {
+                    r = Err(e);
+                    break;
+                }
+            }
         }
+        for s in &self.teardowns {
+            match s.execute(self.ctx.as_mut()).await {
+                Err(e) if r.is_ok() => r = Err(e),
+                _ => continue
+            }
+        }
+
+        self.ctx.close_db_connections().await;
 
         debug!("store:{:?}", self.ctx.store);
         self.ctx.store.clear();
-        Ok(())
+        self.ctx.http_sessions.clear();
+        self.ctx.oauth2_tokens.clear();
+        self.ctx.db_connections.clear();
+        r
     }
 }
 
@@ -8,6 +8,7 @@ pub(crate) enum Kind {
     CreateClient,
     Request,
     Decode,
+    Io,
 }
 
 pub struct Error {
@@ -43,6 +44,10 @@ pub(crate) fn request(e: InnerError) -> Error {
     Error::new(Kind::Request, Some(e))
 }
 
+pub(crate) fn io(e: InnerError) -> Error {
+    Error::new(Kind::Io, Some(e))
+}
+
 pub(crate) fn unimplement(e: &str) -> Error {
     Error::new(Kind::ParseValue, Some(e))
 }
@@ -9,8 +9,9 @@ pub mod context;
 pub mod capture;
 pub mod de;
 pub mod report;
+pub mod tls;
 
-type Result<T> = std::result::Result<T, crate::error::Error>;
+pub type Result<T> = std::result::Result<T, crate::error::Error>;
 
 
 #[cfg(test)]
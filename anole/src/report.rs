@@ -25,6 +25,11 @@ impl ReportItem {
         }
     }
 
+    /// Whether task is succeed.
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
 }
 
 impl std::fmt::Display for ReportItem {
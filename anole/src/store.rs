@@ -3,12 +3,12 @@ use std::collections::HashMap;
 use crate::value::Value;
 
 /// Store captured values for using between tasks.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Store {
     pub(crate) data: HashMap<String, Value>,
 }
 
-impl <'a> Store {
+impl Store {
     pub(crate) fn new() -> Store {
         Store { data: HashMap::new() }
     }
@@ -28,4 +28,15 @@ impl <'a> Store {
     pub fn clear(&mut self) {
         self.data.clear()
     }
+
+    /// Get value by key, or address into a captured structured value, e.g. `user.roles|0`.
+    pub fn lookup(&self, k: &str) -> Option<Value> {
+        if let Some(v) = self.data.get(k) {
+            return Some(v.to_owned());
+        }
+        let idx = k.find(['.', '|'])?;
+        let root = self.data.get(&k[..idx])?;
+        let path = k[idx..].trim_start_matches('.');
+        root.get_path(path).cloned()
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 
+#[allow(dead_code)]
 pub(crate) trait Chain<T, R> {
     fn next(t: T) -> R;
 }
\ No newline at end of file
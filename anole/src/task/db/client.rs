@@ -0,0 +1,288 @@
+use futures_util::TryStreamExt;
+use sqlx::{database::HasArguments, types::time, query::Query, Database, Either, Executor, IntoArguments, MySql, Mssql, Pool, Postgres, Sqlite, Transaction};
+
+use crate::{context::Context, report::ReportItem, value::Value};
+
+use super::{Action, CaptureRow, DBClientOption, DBTask, ExecResult, QueryResult, mysql, postgres, sqlite, mssql};
+
+
+/// Database driver selected by url scheme.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Driver {
+    Mysql,
+    Postgres,
+    Sqlite,
+    Mssql,
+}
+
+impl Driver {
+
+    /// Driver of `mysql://`, `mariadb://`, `postgres://`, `postgresql://`, `sqlite:` and `mssql://`, `sqlserver://` urls.
+    pub fn from_url(url: &str) -> crate::Result<Self> {
+        let scheme = match url.split_once(':') {
+            Some((s, _)) => s.to_ascii_lowercase(),
+            None => return Err(crate::error::create_client(format!("{} has no scheme", url).into()))
+        };
+        match scheme.as_str() {
+            "mysql" | "mariadb" => Ok(Driver::Mysql),
+            "postgres" | "postgresql" => Ok(Driver::Postgres),
+            "sqlite" => Ok(Driver::Sqlite),
+            "mssql" | "sqlserver" => Ok(Driver::Mssql),
+            _ => Err(crate::error::create_client(format!("unsupport database scheme {}", scheme).into()))
+        }
+    }
+
+    /// Bind placeholder of the nth (starts from 1) argument `v`.
+    /// The MSSQL driver can't encode binary, so bytes are bound as hex text and converted back by the server.
+    pub(crate) fn placeholder(&self, n: usize, v: &Value) -> String {
+        match (self, v) {
+            (Driver::Mysql | Driver::Sqlite, _) => "?".to_string(),
+            (Driver::Postgres, _) => format!("${}", n),
+            (Driver::Mssql, Value::Bytes(_)) => format!("CONVERT(VARBINARY(MAX), @p{}, 2)", n),
+            (Driver::Mssql, _) => format!("@p{}", n),
+        }
+    }
+
+    /// SQL literal of a store value, fails for NaN and infinite floats which have no literal.
+    pub(crate) fn literal(&self, v: &Value) -> Result<String, String> {
+        Ok(match v {
+            Value::F64(f) if !f.is_finite() => return Err(format!("{} has no SQL literal", f)),
+            Value::I32(_) | Value::U32(_) | Value::I64(_) | Value::U64(_) | Value::F64(_) => v.as_str(),
+            Value::Bool(b) => match (self, b) {
+                (Driver::Postgres, true) => "TRUE".to_string(),
+                (Driver::Postgres, false) => "FALSE".to_string(),
+                (_, true) => "1".to_string(),
+                (_, false) => "0".to_string(),
+            },
+            Value::Str(s) => self.quote(s),
+            Value::Date(d) => self.quote(&d.format("%F")),
+            Value::Time(t) => self.quote(&t.format("%H:%M:%S")),
+            Value::Decimal(d) => d.to_string(),
+            Value::DateTime(t) => match self {
+                Driver::Postgres => self.quote(&v.as_str()),
+                _ => self.quote(&t.to_offset(time::UtcOffset::UTC).format("%F %H:%M:%S")),
+            },
+            Value::Bytes(_) => match self {
+                Driver::Postgres => format!("'\\x{}'", v.as_str()),
+                Driver::Mysql | Driver::Sqlite => format!("X'{}'", v.as_str()),
+                Driver::Mssql => format!("0x{}", v.as_str()),
+            },
+            Value::Array(_) | Value::Object(_) => self.quote(&v.as_str()),
+            Value::Null => "NULL".to_string(),
+        })
+    }
+
+    fn quote(&self, s: &str) -> String {
+        let s = s.replace('\'', "''");
+        match self {
+            // backslash is an escape character in MySQL string literals.
+            Driver::Mysql => format!("'{}'", s.replace('\\', "\\\\")),
+            _ => format!("'{}'", s),
+        }
+    }
+}
+
+/// Connection pool of a database driver.
+#[derive(Debug, Clone)]
+pub(crate) enum DBClient {
+    Mysql(Pool<MySql>),
+    Postgres(Pool<Postgres>),
+    Sqlite(Pool<Sqlite>),
+    Mssql(Pool<Mssql>),
+}
+
+impl DBClient {
+
+    /// Connect with the driver given, or the driver of url scheme.
+    pub(crate) async fn connect(options: &DBClientOption<'_>, driver: Option<Driver>) -> crate::Result<Self> {
+        let driver = match driver {
+            Some(d) => d,
+            None => Driver::from_url(options.url)?
+        };
+        match driver {
+            Driver::Mysql => mysql::connect(options).await.map(DBClient::Mysql),
+            Driver::Postgres => postgres::connect(options).await.map(DBClient::Postgres),
+            Driver::Sqlite => sqlite::connect(options).await.map(DBClient::Sqlite),
+            Driver::Mssql => mssql::connect(options).await.map(DBClient::Mssql),
+        }
+    }
+
+    /// Execute database tasks in order and report their results.
+    /// With `rollback`, all tasks execute in one transaction which is rolled back at the end.
+    pub(crate) async fn run(&self, tasks: &[DBTask<'_>], rollback: bool, ctx: &mut Context) -> crate::Result<()> {
+        match self {
+            DBClient::Mysql(pool) => run_tasks(pool, tasks, rollback, ctx).await,
+            DBClient::Postgres(pool) => run_tasks(pool, tasks, rollback, ctx).await,
+            DBClient::Sqlite(pool) => run_tasks(pool, tasks, rollback, ctx).await,
+            DBClient::Mssql(pool) => run_tasks(pool, tasks, rollback, ctx).await,
+        }
+    }
+
+    /// Execute sql of multiple statements without bind arguments.
+    pub(crate) async fn run_script(&self, sql: &str) -> crate::Result<()> {
+        match self {
+            DBClient::Mysql(pool) => execute_all(pool, sql).await,
+            DBClient::Postgres(pool) => execute_all(pool, sql).await,
+            DBClient::Sqlite(pool) => execute_all(pool, sql).await,
+            DBClient::Mssql(pool) => execute_all(pool, sql).await,
+        }
+    }
+
+    pub(crate) fn driver(&self) -> Driver {
+        match self {
+            DBClient::Mysql(_) => Driver::Mysql,
+            DBClient::Postgres(_) => Driver::Postgres,
+            DBClient::Sqlite(_) => Driver::Sqlite,
+            DBClient::Mssql(_) => Driver::Mssql,
+        }
+    }
+
+    pub(crate) async fn close(&self) {
+        match self {
+            DBClient::Mysql(pool) => pool.close().await,
+            DBClient::Postgres(pool) => pool.close().await,
+            DBClient::Sqlite(pool) => pool.close().await,
+            DBClient::Mssql(pool) => pool.close().await,
+        }
+    }
+}
+
+/// Database of a driver.
+pub(crate) trait Backend: Database {
+    const DRIVER: Driver;
+
+    fn bind<'q>(q: Query<'q, Self, <Self as HasArguments<'q>>::Arguments>, v: &Value) -> Query<'q, Self, <Self as HasArguments<'q>>::Arguments>;
+}
+
+async fn run_tasks<DB>(pool: &Pool<DB>, tasks: &[DBTask<'_>], rollback: bool, ctx: &mut Context) -> crate::Result<()>
+where
+    DB: Backend,
+    DB::Row: CaptureRow,
+    DB::QueryResult: QueryResult,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    let mut tx = None;
+    if rollback {
+        match pool.begin().await {
+            Ok(t) => tx = Some(t),
+            Err(e) => return Err(crate::error::request(e.into()))
+        }
+    }
+    let mut r = Ok(());
+    for t in tasks {
+        match execute(pool, &mut tx, t, rollback, ctx).await {
+            Ok(item) => ctx.report(item),
+            Err(e) => {
+                r = Err(e);
+                break;
+            }
+        }
+    }
+    // a transaction begun by task but not committed fails the begin task.
+    if !rollback && tx.is_some() && r.is_ok() {
+        if let Some(begin) = tasks.iter().rev().find(|t| t.action == Action::Begin) {
+            ctx.report(ReportItem::failed(&begin.task_id, "transaction begun but not committed, rolled back".to_string()));
+        }
+    }
+    // roll back the transaction left open by rollback mode, a missing commit or a failed task.
+    if let Some(t) = tx {
+        if let Err(e) = t.rollback().await {
+            if r.is_ok() {
+                r = Err(crate::error::request(e.into()));
+            }
+        }
+    }
+    r
+}
+
+async fn execute<DB>(pool: &Pool<DB>, tx: &mut Option<Transaction<'static, DB>>, t: &DBTask<'_>, rollback: bool, ctx: &mut Context) -> crate::Result<ReportItem>
+where
+    DB: Backend,
+    DB::Row: CaptureRow,
+    DB::QueryResult: QueryResult,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    match t.action {
+        Action::Query => (),
+        _ if rollback => return Ok(ReportItem::failed(&t.task_id, "transaction is rolled back at the end of task".to_string())),
+        Action::Begin => {
+            if tx.is_some() {
+                return Ok(ReportItem::failed(&t.task_id, "transaction already begun".to_string()));
+            }
+            return match pool.begin().await {
+                Ok(b) => {
+                    *tx = Some(b);
+                    Ok(ReportItem::success(&t.task_id, "transaction begun".to_string()))
+                },
+                Err(e) => Err(crate::error::request(e.into()))
+            };
+        },
+        Action::Commit | Action::Rollback => {
+            let b = match tx.take() {
+                Some(b) => b,
+                None => return Ok(ReportItem::failed(&t.task_id, "no transaction begun".to_string()))
+            };
+            let (r, msg) = if t.action == Action::Commit {
+                (b.commit().await, "transaction committed")
+            } else {
+                (b.rollback().await, "transaction rolled back")
+            };
+            return match r {
+                Ok(_) => Ok(ReportItem::success(&t.task_id, msg.to_string())),
+                Err(e) => Err(crate::error::request(e.into()))
+            };
+        },
+    }
+
+    if t.wants_last_insert_id() && matches!(DB::DRIVER, Driver::Postgres | Driver::Mssql) {
+        return Ok(ReportItem::failed(&t.task_id, "last_insert_id is not supported, capture RETURNING or OUTPUT columns instead".to_string()));
+    }
+    let (sql, args) = match t.bind_sql(ctx, DB::DRIVER) {
+        Ok(r) => r,
+        Err(p) => return Ok(ReportItem::failed(&t.task_id, format!("param {} not found", p)))
+    };
+    let query = args.iter().fold(sqlx::query(&sql), DB::bind);
+    let fetched = match tx {
+        Some(b) => fetch(query, &mut **b).await,
+        None => fetch(query, pool).await
+    };
+    match fetched {
+        Ok((rows, result)) => t.handle_rows(&rows, &result, ctx),
+        Err(e) => Err(e)
+    }
+}
+
+/// Execute all statements of query, returns the rows and statement results.
+async fn fetch<'e, 'c: 'e, 'q: 'e, DB, E>(query: Query<'q, DB, <DB as HasArguments<'q>>::Arguments>, executor: E) -> crate::Result<(Vec<DB::Row>, ExecResult)>
+where
+    DB: Database,
+    DB::QueryResult: QueryResult,
+    <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    E: 'e + Executor<'c, Database = DB>,
+{
+    let mut stream = query.fetch_many(executor);
+    let mut rows = vec![];
+    let mut result = ExecResult::default();
+    loop {
+        match stream.try_next().await {
+            Ok(Some(Either::Left(r))) => result.add(&r),
+            Ok(Some(Either::Right(row))) => rows.push(row),
+            Ok(None) => break,
+            Err(e) => return Err(crate::error::request(e.into()))
+        }
+    }
+    Ok((rows, result))
+}
+
+async fn execute_all<'c, E: Executor<'c>>(executor: E, sql: &str) -> crate::Result<()> {
+    let mut stream = executor.execute_many(sql);
+    loop {
+        match stream.try_next().await {
+            Ok(Some(_)) => (),
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(crate::error::request(e.into()))
+        }
+    }
+}
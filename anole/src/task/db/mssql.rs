@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use sqlx::{mssql::{self, *}, pool::PoolOptions, query::Query, types::time, Column, Pool, Row, Type, ConnectOptions};
+
+use crate::{capture::CapValueType, value::Value};
+
+use super::{DBClientOption, CaptureRow, decode, widen, QueryResult, client::{Backend, Driver}};
+
+
+impl CaptureRow for MssqlRow {
+    fn capture(&self, key: &str, t: &CapValueType) -> Result<Value, String> {
+        match t {
+            CapValueType::Size | CapValueType::I64 => decode(self, key, |v: i64| Ok(Value::I64(v))),
+            CapValueType::I32 => match column(self, key)? {
+                // TINYINT is unsigned.
+                ty if <i8 as Type<Mssql>>::compatible(ty) => decode(self, key, |v: i8| Ok(Value::I32((v as u8).into()))),
+                ty if <i16 as Type<Mssql>>::compatible(ty) => decode(self, key, |v: i16| Ok(Value::I32(v.into()))),
+                _ => decode(self, key, |v: i32| Ok(Value::I32(v))),
+            },
+            CapValueType::U32 => decode(self, key, |v: i64| u32::try_from(v).map(Value::U32).map_err(|e| e.to_string())),
+            CapValueType::U64 => decode(self, key, |v: i64| u64::try_from(v).map(Value::U64).map_err(|e| e.to_string())),
+            CapValueType::Bool => decode(self, key, |v: bool| Ok(Value::Bool(v))),
+            CapValueType::Str => decode(self, key, |v: String| Ok(Value::Str(v))),
+            CapValueType::F64 => match column(self, key)? {
+                ty if <f32 as Type<Mssql>>::compatible(ty) => decode(self, key, |v: f32| Ok(Value::F64(widen(v)))),
+                _ => decode(self, key, |v: f64| Ok(Value::F64(v))),
+            },
+            _ => Err(format!("unsupport type {:?}", t)),
+        }
+    }
+
+    /// Inferred by the Rust types compatible with column, the driver has no names for most SQL types.
+    fn column_type(&self, key: &str) -> Result<CapValueType, String> {
+        let ty = column(self, key)?;
+        if <bool as Type<Mssql>>::compatible(ty) {
+            Ok(CapValueType::Bool)
+        } else if <i8 as Type<Mssql>>::compatible(ty) || <i16 as Type<Mssql>>::compatible(ty) || <i32 as Type<Mssql>>::compatible(ty) {
+            Ok(CapValueType::I32)
+        } else if <i64 as Type<Mssql>>::compatible(ty) {
+            Ok(CapValueType::I64)
+        } else if <f32 as Type<Mssql>>::compatible(ty) || <f64 as Type<Mssql>>::compatible(ty) {
+            Ok(CapValueType::F64)
+        } else if <String as Type<Mssql>>::compatible(ty) {
+            Ok(CapValueType::Str)
+        } else {
+            Err(format!("unsupport column type {:?}", ty))
+        }
+    }
+}
+
+fn column<'r>(row: &'r MssqlRow, key: &str) -> Result<&'r MssqlTypeInfo, String> {
+    match row.columns().iter().find(|c| c.name() == key) {
+        Some(c) => Ok(c.type_info()),
+        None => Err(format!("no column found for name: {}", key))
+    }
+}
+
+impl QueryResult for MssqlQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected()
+    }
+
+    fn last_insert_id(&self) -> Option<i64> {
+        None
+    }
+}
+
+/// Connect MSSQL pool, TLS is not supported by the driver.
+pub(crate) async fn connect(options: &DBClientOption<'_>) -> crate::Result<Pool<Mssql>> {
+    if options.tls.is_some() {
+        return Err(crate::error::create_client("TLS is not supported for MSSQL".into()));
+    }
+    let mut opts = match options.url.parse::<mssql::MssqlConnectOptions>() {
+        Ok(o) => o,
+        Err(e) => return Err(crate::error::create_client(e.into()))
+    };
+    opts.disable_statement_logging();
+    match PoolOptions::<Mssql>::new()
+        .connect_timeout(Duration::from_secs(5))
+        .idle_timeout(Duration::from_secs(20))
+        .max_connections(options.max_connections)
+        .connect_with(opts).await {
+            Ok(p) => Ok(p),
+            Err(e) => Err(crate::error::create_client(e.into()))
+        }
+}
+
+impl Backend for Mssql {
+    const DRIVER: Driver = Driver::Mssql;
+
+    /// Bind store value as a typed query argument, dates, times, decimals and date times are bound as text,
+    /// binary as hex text which the placeholder converts to `VARBINARY`.
+    fn bind<'q>(q: Query<'q, Mssql, MssqlArguments>, v: &Value) -> Query<'q, Mssql, MssqlArguments> {
+        match v {
+            Value::I32(i) => q.bind(*i),
+            Value::U32(u) => q.bind(*u as i64),
+            Value::I64(i) => q.bind(*i),
+            Value::U64(u) => match i64::try_from(*u) {
+                Ok(i) => q.bind(i),
+                Err(_) => q.bind(u.to_string())
+            },
+            Value::F64(f) => q.bind(*f),
+            Value::Bool(b) => q.bind(*b),
+            Value::Str(s) => q.bind(s.to_owned()),
+            Value::Date(d) => q.bind(d.format("%F")),
+            Value::Time(t) => q.bind(t.format("%H:%M:%S")),
+            Value::Decimal(d) => q.bind(d.to_string()),
+            Value::DateTime(t) => q.bind(t.to_offset(time::UtcOffset::UTC).format("%F %H:%M:%S")),
+            Value::Array(_) | Value::Object(_) | Value::Bytes(_) => q.bind(v.as_str()),
+            Value::Null => q.bind(Option::<String>::None),
+        }
+    }
+}
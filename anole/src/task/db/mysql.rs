@@ -1,216 +1,123 @@
 
-use std::{vec, time::Duration};
+use std::time::Duration;
 
-use sqlx::{mysql::{self, *}, Pool, Row, types::time, ConnectOptions};
+use sqlx::{mysql::{self, *}, query::Query, Pool, types::{time, Decimal}, ConnectOptions};
 
-use crate::{context::Context, task, capture::Capture, value::Value, faker, report::ReportItem};
+use crate::{capture::CapValueType, value::Value};
 
-use super::DBClientOption;
+use super::{DBClientOption, CaptureRow, decode, type_name, widen, QueryResult, client::{Backend, Driver}};
 
+pub use super::DBTask;
 
-/// An MySQL task. 
-pub struct MysqlTask<'a> {
-    //options
-    options: Option<DBClientOption<'a>>,
-    tasks: Vec<DBTask<'a>>,
-}
-
-impl<'a> MysqlTask<'a> {
 
-    pub fn default() -> Self {
-        MysqlTask { options: None, tasks: vec![] }
-    }
-    
-    /// Specify MySQL client connection options.
-    pub fn options(mut self, options: task::db::DBClientOption<'a>) -> Self {
-        self.options = Some(options);
-        self
-    }
-
-    /// Add an database task. 
-    pub fn with_task(mut self, t: DBTask<'a>) -> Self {
-        self.tasks.push(t);
-        self
-    }
-
-    /// Execute all database task.
-    pub async fn execute(&self, ctx: &mut Context) -> crate::Result<()> {
-        let options = match &self.options {
-            Some(o) => o,
-            None => return Err(crate::error::create_client("DBClientOptions Empty".into()))
-        };
-        let mut client = MysqlClient::default();
-        match client.create(options).await {
-            Ok(_) => (),
-            Err(e) => return Err(e)
-        };
-
-        for tt in &self.tasks {
-            match client.execute(tt, ctx).await {
-                Ok(r) => {
-                    ctx.report(r);
-                    continue;
-                },
-                Err(e) => return Err(e)
-            }
+/// An MySQL task. 
+pub type MysqlTask<'a> = super::DBTaskGroup<'a, MySql>;
+
+impl CaptureRow for MySqlRow {
+    fn capture(&self, key: &str, t: &CapValueType) -> Result<Value, String> {
+        match t {
+            CapValueType::Size | CapValueType::I64 => decode(self, key, |v: i64| Ok(Value::I64(v))),
+            CapValueType::I32 => decode(self, key, |v: i32| Ok(Value::I32(v))),
+            CapValueType::U32 => decode(self, key, |v: u32| Ok(Value::U32(v))),
+            CapValueType::U64 => decode(self, key, |v: u64| Ok(Value::U64(v))),
+            CapValueType::Bool => decode(self, key, |v: bool| Ok(Value::Bool(v))),
+            CapValueType::Str => decode(self, key, |v: &str| Ok(Value::Str(v.to_string()))),
+            CapValueType::Date => decode(self, key, |v: time::Date| Ok(Value::Date(v))),
+            CapValueType::Time => decode(self, key, |v: time::Time| Ok(Value::Time(v))),
+            CapValueType::Json => decode(self, key, |v: serde_json::Value| Ok(Value::from(&v))),
+            CapValueType::Decimal => decode(self, key, |v: Decimal| Ok(Value::Decimal(v))),
+            // TIMESTAMP columns are in UTC, DATETIME columns have no time zone.
+            CapValueType::Timestamp => decode(self, key, |v: time::OffsetDateTime| Ok(Value::DateTime(v))),
+            CapValueType::F64 => match type_name(self, key)?.as_str() {
+                "FLOAT" => decode(self, key, |v: f32| Ok(Value::F64(widen(v)))),
+                _ => decode(self, key, |v: f64| Ok(Value::F64(v))),
+            },
+            CapValueType::Bytes => decode(self, key, |v: Vec<u8>| Ok(Value::Bytes(v))),
+            _ => Err(format!("unsupport type {:?}", t)),
         }
-        Ok(())
     }
-}
 
-/// Database task.
-#[derive(Debug)]
-pub struct DBTask<'a> {
-    pub sql: &'a str,
-    params: Option<Vec<&'a str>>,
-    capture: Option<Vec<Capture<'a>>>,
-    expect: Option<(&'a str, Value)>,
-    pub task_id: String,
-}
-
-impl <'a> DBTask<'a> {
-    
-    pub fn new(sql: &'a str) -> Self {
-        DBTask {
-            sql,
-            params: None,
-            capture: None,
-            task_id: faker::uuid_v4(),
-            expect: None,
+    fn column_type(&self, key: &str) -> Result<CapValueType, String> {
+        let name = type_name(self, key)?;
+        match name.as_str() {
+            "BOOLEAN" => Ok(CapValueType::Bool),
+            "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" => Ok(CapValueType::I32),
+            "BIGINT" => Ok(CapValueType::I64),
+            "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "YEAR" => Ok(CapValueType::U32),
+            "BIGINT UNSIGNED" => Ok(CapValueType::U64),
+            "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET" | "NULL" => Ok(CapValueType::Str),
+            "DATE" => Ok(CapValueType::Date),
+            "TIME" => Ok(CapValueType::Time),
+            "DATETIME" | "TIMESTAMP" => Ok(CapValueType::Timestamp),
+            "DECIMAL" => Ok(CapValueType::Decimal),
+            "JSON" => Ok(CapValueType::Json),
+            "FLOAT" | "DOUBLE" => Ok(CapValueType::F64),
+            "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => Ok(CapValueType::Bytes),
+            _ => Err(format!("unsupport column type {}", name)),
         }
     }
+}
 
-    pub fn param(mut self, p: &'a str) -> Self {
-        let params =  self.params.get_or_insert(vec![]);
-        params.push(p);
-        self
-    }
-
-    pub fn capture(mut self, caps: Vec<Capture<'a>>) -> Self {
-        self.capture = Some(caps);
-        self
-    }
-
-    pub fn expect(mut self, tup: (&'a str, Value)) -> Self {
-        self.expect = Some(tup);
-        self
+impl QueryResult for MySqlQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected()
     }
 
-    pub(crate) fn handle_rows(&self, rows: &[MySqlRow], ctx: &mut Context) -> crate::Result<ReportItem> {
-        if let Some(ref _caps) = self.capture {
-            for (idx, r) in rows.iter().enumerate() {
-                for _cap in _caps {
-                    if let Capture::Column(ref _c) = _cap {
-                        let mut _save_key = _c.save_key.to_owned();
-                        if rows.len() > 1 {
-                            _save_key = _save_key + "|" + &idx.to_string();
-                            ctx.store.set(_c.save_key.to_owned(), Value::U32(idx as u32));
-                        }
-                        if _c.is_usize() {
-                            if let Ok(vv) = r.try_get::<i64, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::I64(vv));
-                            }
-                        } else if _c.is_i32() {
-                            if let Ok(vv) = r.try_get::<i32, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::I32(vv));
-                            }
-                        } else if _c.is_u32() {
-                            if let Ok(vv) = r.try_get::<u32, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::U32(vv));
-                            }
-                        } else if _c.is_i64() {
-                            if let Ok(vv) = r.try_get::<i64, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::I64(vv));
-                            }
-                        } else if _c.is_u64() {
-                            if let Ok(vv) = r.try_get::<u64, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::U64(vv));
-                            }
-                        } else if _c.is_bool() {
-                            if let Ok(vv) = r.try_get::<bool, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::Bool(vv));
-                            }
-                        } else if _c.is_str() {
-                            if let Ok(vv) = r.try_get::<&str, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::Str(vv.to_string()));
-                            }
-                        } else if _c.is_date() {
-                            if let Ok(vv) = r.try_get::<time::Date, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::Date(vv));
-                            }
-                        } else if _c.is_time() {
-                            if let Ok(vv) = r.try_get::<time::Time, &str>(_c.key) {
-                                ctx.store.set(_save_key, Value::Time(vv));
-                            }
-                        } else {
-                            return Err(crate::error::unimplement("unsupport type"));
-                        }
-                    }
-                }
-            }
-        }
-
-        let task_id = self.task_id.to_owned();
-        if let Some(_expect) = &self.expect {
-            if let Some(_value) = ctx.store.get(_expect.0.to_string()) {
-                if _value == &_expect.1 {
-                    return Ok(ReportItem::success(&task_id, format!("{} expect {:?} pass", _expect.0, _expect.1)))
-                } else {
-                    return Ok(ReportItem::failed(&task_id, format!("{} expect {:?} but {:?}", _expect.0, _expect.1, _value)))
-                }
-            } else {
-                return Ok(ReportItem::failed(&task_id, format!("{} expect {:?} but not found", _expect.0, _expect.1)))
-            }
+    fn last_insert_id(&self) -> Option<i64> {
+        match self.last_insert_id() {
+            0 => None,
+            id => Some(id as i64)
         }
-        Ok(ReportItem::success(&task_id, "database execute succeed".to_string()))
     }
-
-}
-
-#[derive(Debug, Default)]
-struct MysqlClient {
-    pool: Option<Pool<MySql>>
 }
 
-impl MysqlClient {
-
-    async fn create(&mut self, options: &DBClientOption<'_>) -> crate::Result<()> {
-        let mut opts = match options.url.parse::<mysql::MySqlConnectOptions>() {
-            Ok(o) => o,
-            Err(e) => return Err(crate::error::create_client(e.into()))
+pub(crate) async fn connect(options: &DBClientOption<'_>) -> crate::Result<Pool<MySql>> {
+    let mut opts = match options.url.parse::<mysql::MySqlConnectOptions>() {
+        Ok(o) => o,
+        Err(e) => return Err(crate::error::create_client(e.into()))
+    };
+    opts.disable_statement_logging();
+    if let Some(ref tls) = options.tls {
+        tls.check_db_support()?;
+        opts = if tls.accept_invalid_certs {
+            opts.ssl_mode(MySqlSslMode::Required)
+        } else {
+            opts.ssl_mode(MySqlSslMode::VerifyIdentity)
         };
-        opts.disable_statement_logging();
-        let pool = match MySqlPoolOptions::new()
-            .connect_timeout(Duration::from_secs(5))
-            .idle_timeout(Duration::from_secs(20))
-            .max_connections(options.max_connections)
-            .connect_with(opts).await {
-                Ok(p) => p,
-                Err(e) => return Err(crate::error::create_client(e.into()))
-            };
-
-        self.pool = Some(pool);
-        
-        Ok(())
+        if let Some(pem) = tls.root_certs_pem()? {
+            opts = opts.ssl_ca_from_pem(pem);
+        }
     }
+    match MySqlPoolOptions::new()
+        .connect_timeout(Duration::from_secs(5))
+        .idle_timeout(Duration::from_secs(20))
+        .max_connections(options.max_connections)
+        .connect_with(opts).await {
+        Ok(p) => Ok(p),
+        Err(e) => Err(crate::error::create_client(e.into()))
+    }
+}
 
-    async fn execute(&self, t: &DBTask<'_>, ctx: &mut Context) -> crate::Result<ReportItem> {
-        let pool = &self.pool.as_ref().unwrap();
-
-        let mut sql = t.sql.to_owned();
-
-        if let Some(_params) = &t.params {
-            for _k in _params {
-                if let Some(v) = ctx.store.get(_k.to_string()) {
-                    sql = sql.replace(format!(":{}", _k).as_str(), v.as_str().as_str());
-                }
-            }
+impl Backend for MySql {
+    const DRIVER: Driver = Driver::Mysql;
+
+    /// Bind store value as a typed query argument.
+    fn bind<'q>(q: Query<'q, MySql, MySqlArguments>, v: &Value) -> Query<'q, MySql, MySqlArguments> {
+        match v {
+            Value::I32(i) => q.bind(*i),
+            Value::U32(u) => q.bind(*u),
+            Value::I64(i) => q.bind(*i),
+            Value::U64(u) => q.bind(*u),
+            Value::F64(f) => q.bind(*f),
+            Value::Bool(b) => q.bind(*b),
+            Value::Str(s) => q.bind(s.to_owned()),
+            Value::Date(d) => q.bind(*d),
+            Value::Time(t) => q.bind(*t),
+            Value::Decimal(d) => q.bind(*d),
+            Value::DateTime(t) => q.bind(*t),
+            Value::Bytes(b) => q.bind(b.to_owned()),
+            Value::Array(_) | Value::Object(_) => q.bind(v.as_str()),
+            Value::Null => q.bind(Option::<String>::None),
         }
-        let rows = match sqlx::query(&sql).fetch_all(*pool).await {
-            Ok(r) => r,
-            Err(e) => return Err(crate::error::request(e.into()))
-        };
-        t.handle_rows(&rows, ctx)
     }
-
 }
@@ -0,0 +1,86 @@
+use crate::context::Context;
+
+use super::{DBClientOption, Driver, replace_placeholders};
+
+
+enum Source<'a> {
+    File(&'a str),
+    Sql(&'a str),
+}
+
+/// SQL script of multiple statements, runs as setup before or teardown after an engine run.
+///
+/// `:key` placeholders are replaced with store values rendered as SQL literals,
+/// placeholders without store value are kept.
+/// # Example
+///
+/// ```
+/// use anole::{engine::Engine, task::db::{script::SqlScript, DBClientOption}};
+///
+/// let engine = Engine::new()
+///     .with_setup(SqlScript::file("fixtures/seed.sql")
+///         .options(DBClientOption::builder().url("sqlite://target/app.db")))
+///     .with_teardown(SqlScript::sql("DELETE FROM users WHERE id = :user_id; DELETE FROM orders;")
+///         .options(DBClientOption::builder().url("sqlite://target/app.db")));
+/// ```
+pub struct SqlScript<'a> {
+    source: Source<'a>,
+    options: Option<DBClientOption<'a>>,
+    connection: Option<&'a str>,
+    driver: Option<Driver>,
+}
+
+impl<'a> SqlScript<'a> {
+
+    /// Script of `.sql` file.
+    pub fn file(path: &'a str) -> Self {
+        SqlScript { source: Source::File(path), options: None, connection: None, driver: None }
+    }
+
+    /// Script of sql statements.
+    pub fn sql(sql: &'a str) -> Self {
+        SqlScript { source: Source::Sql(sql), options: None, connection: None, driver: None }
+    }
+
+    /// Specify database client connection options, the driver is selected by url scheme.
+    pub fn options(mut self, options: DBClientOption<'a>) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Use the named engine connection declared by `Engine::with_database_connection` instead of connecting by options.
+    pub fn connection(mut self, name: &'a str) -> Self {
+        self.connection = Some(name);
+        self
+    }
+
+    /// Use the driver instead of the one of url scheme.
+    pub fn driver(mut self, driver: Driver) -> Self {
+        self.driver = Some(driver);
+        self
+    }
+
+    /// Execute all statements of script.
+    pub async fn execute(&self, ctx: &mut Context) -> crate::Result<()> {
+        let sql = match self.source {
+            Source::File(path) => match std::fs::read_to_string(path) {
+                Ok(s) => s,
+                Err(e) => return Err(crate::error::io(e.into()))
+            },
+            Source::Sql(sql) => sql.to_string(),
+        };
+        let (client, owned) = match super::task_client(self.options.as_ref(), self.connection, self.driver, ctx).await {
+            Ok(c) => c,
+            Err(e) => return Err(e)
+        };
+        let driver = client.driver();
+        let r = match replace_placeholders(&sql, driver, |name| ctx.store.get(name.to_string()).map(|v| driver.literal(v)).transpose()) {
+            Ok(sql) => client.run_script(&sql).await,
+            Err(e) => Err(crate::error::parse_value(e.into()))
+        };
+        if owned {
+            client.close().await;
+        }
+        r
+    }
+}
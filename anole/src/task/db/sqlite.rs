@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use sqlx::{sqlite::{self, *}, query::Query, Pool, types::{time, Decimal, Uuid}, ConnectOptions, Row, TypeInfo, ValueRef};
+
+use crate::{capture::CapValueType, value::{self, Value}};
+
+use super::{DBClientOption, CaptureRow, decode, type_name, QueryResult, client::{Backend, Driver}};
+
+
+/// An SQLite task.
+///
+/// Url is either `sqlite::memory:` for an in-memory database, or `sqlite://path/to/file.db`
+/// for a database file, append `?mode=rwc` to create the file if missing.
+/// # Example
+///
+/// ```
+/// use anole::{task::db::{sqlite::SqliteTask, DBTask, DBClientOption}, capture::{self, CapValueType}};
+///
+/// let sqlite_task = SqliteTask::default()
+///     .options(DBClientOption::builder().url("sqlite://target/app.db"))
+///     .with_task(DBTask::new("SELECT id, name FROM users WHERE name = :user_name")
+///         .param("user_name")
+///         .capture(vec![
+///             capture::column("id", "user_id", CapValueType::I64),
+///         ]));
+/// ```
+pub type SqliteTask<'a> = super::DBTaskGroup<'a, Sqlite>;
+
+impl CaptureRow for SqliteRow {
+    fn capture(&self, key: &str, t: &CapValueType) -> Result<Value, String> {
+        match t {
+            CapValueType::Size | CapValueType::I64 => decode(self, key, |v: i64| Ok(Value::I64(v))),
+            CapValueType::I32 => decode(self, key, |v: i32| Ok(Value::I32(v))),
+            CapValueType::U32 => decode(self, key, |v: u32| Ok(Value::U32(v))),
+            CapValueType::U64 => decode(self, key, |v: i64| u64::try_from(v).map(Value::U64).map_err(|e| e.to_string())),
+            CapValueType::Bool => decode(self, key, |v: bool| Ok(Value::Bool(v))),
+            CapValueType::Str => decode(self, key, |v: &str| Ok(Value::Str(v.to_string()))),
+            // SQLite has no date types, dates are stored as `YYYY-MM-DD`, `HH:MM:SS` and `YYYY-MM-DD HH:MM:SS` text.
+            CapValueType::Date => decode(self, key, |v: &str| time::Date::parse(v, "%F")
+                .map(Value::Date).map_err(|e| format!("{} is not a date: {}", v, e))),
+            CapValueType::Time => decode(self, key, |v: &str| time::Time::parse(v, "%T")
+                .map(Value::Time).map_err(|e| format!("{} is not a time: {}", v, e))),
+            CapValueType::Timestamp => decode(self, key, |v: &str| value::parse_datetime(v)
+                .map(Value::DateTime).ok_or(format!("{} is not a timestamp", v))),
+            // numbers are stored as integer, real or text by the affinity of column.
+            CapValueType::F64 => match storage_class(self, key)?.as_str() {
+                "INTEGER" => decode(self, key, |v: i64| Ok(Value::F64(v as f64))),
+                _ => decode(self, key, |v: f64| Ok(Value::F64(v))),
+            },
+            CapValueType::Decimal => match storage_class(self, key)?.as_str() {
+                "INTEGER" => decode(self, key, |v: i64| Ok(Value::Decimal(Decimal::from(v)))),
+                "REAL" => decode(self, key, |v: f64| v.to_string().parse().map(Value::Decimal).map_err(|e| format!("{} is not a decimal: {}", v, e))),
+                _ => decode(self, key, |v: &str| v.parse().map(Value::Decimal).map_err(|e| format!("{} is not a decimal: {}", v, e))),
+            },
+            CapValueType::Bytes => decode(self, key, |v: Vec<u8>| Ok(Value::Bytes(v))),
+            CapValueType::Uuid => decode(self, key, |v: Uuid| Ok(Value::Str(v.to_string()))),
+            CapValueType::Json => decode(self, key, |v: serde_json::Value| Ok(Value::from(&v))),
+            _ => Err(format!("unsupport type {:?}", t)),
+        }
+    }
+
+    /// The declared type of column, or the storage class of value for expression columns.
+    fn column_type(&self, key: &str) -> Result<CapValueType, String> {
+        let name = type_name(self, key)?;
+        match name.as_str() {
+            "INTEGER" => Ok(CapValueType::I64),
+            "BOOLEAN" => Ok(CapValueType::Bool),
+            "TEXT" | "NULL" => Ok(CapValueType::Str),
+            "DATE" => Ok(CapValueType::Date),
+            "TIME" => Ok(CapValueType::Time),
+            "DATETIME" => Ok(CapValueType::Timestamp),
+            "REAL" => Ok(CapValueType::F64),
+            "NUMERIC" => Ok(CapValueType::Decimal),
+            "BLOB" => Ok(CapValueType::Bytes),
+            _ => Err(format!("unsupport column type {}", name)),
+        }
+    }
+}
+
+/// Storage class of value, e.g. `INTEGER` for an integer stored in a `REAL` column.
+fn storage_class(row: &SqliteRow, key: &str) -> Result<String, String> {
+    match row.try_get_raw(key) {
+        Ok(v) => Ok(v.type_info().name().to_string()),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+impl QueryResult for SqliteQueryResult {
+    fn rows_affected(&self) -> u64 {
+        self.rows_affected()
+    }
+
+    fn last_insert_id(&self) -> Option<i64> {
+        match self.last_insert_rowid() {
+            0 => None,
+            id => Some(id)
+        }
+    }
+}
+
+pub(crate) async fn connect(options: &DBClientOption<'_>) -> crate::Result<Pool<Sqlite>> {
+    if options.tls.is_some() {
+        return Err(crate::error::create_client("TLS is not supported for SQLite".into()));
+    }
+    let mut opts = match options.url.parse::<sqlite::SqliteConnectOptions>() {
+        Ok(o) => o,
+        Err(e) => return Err(crate::error::create_client(e.into()))
+    };
+    opts.disable_statement_logging();
+    // in-memory database lives as long as one of its connections, keep them open until the task ends.
+    match SqlitePoolOptions::new()
+        .connect_timeout(Duration::from_secs(5))
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .max_connections(options.max_connections)
+        .connect_with(opts).await {
+        Ok(p) => Ok(p),
+        Err(e) => Err(crate::error::create_client(e.into()))
+    }
+}
+
+impl Backend for Sqlite {
+    const DRIVER: Driver = Driver::Sqlite;
+
+    /// Bind store value as a typed query argument, dates, times and decimals are bound as text.
+    fn bind<'q>(q: Query<'q, Sqlite, SqliteArguments<'q>>, v: &Value) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+        match v {
+            Value::I32(i) => q.bind(*i),
+            Value::U32(u) => q.bind(*u),
+            Value::I64(i) => q.bind(*i),
+            Value::U64(u) => match i64::try_from(*u) {
+                Ok(i) => q.bind(i),
+                Err(_) => q.bind(u.to_string())
+            },
+            Value::F64(f) => q.bind(*f),
+            Value::Bool(b) => q.bind(*b),
+            Value::Str(s) => q.bind(s.to_owned()),
+            Value::Date(d) => q.bind(d.format("%F")),
+            Value::Time(t) => q.bind(t.format("%H:%M:%S")),
+            Value::Decimal(d) => q.bind(d.to_string()),
+            Value::DateTime(t) => q.bind(t.to_offset(time::UtcOffset::UTC).format("%F %H:%M:%S")),
+            Value::Bytes(b) => q.bind(b.to_owned()),
+            Value::Array(_) | Value::Object(_) => q.bind(v.as_str()),
+            Value::Null => q.bind(Option::<String>::None),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{fmt, sync::Arc};
+
+use crate::value::Value;
+
+use super::CaptureRow;
+
+
+/// Assertion on the result set of a database task.
+pub(crate) enum TableExpect {
+    /// Rows equal in any order.
+    Rows(Vec<Value>),
+    /// At least one row matches.
+    Contains(Value),
+    /// Values of column all match the predicate.
+    Column(String, Arc<dyn Fn(&Value) -> bool + Send + Sync>),
+}
+
+impl fmt::Debug for TableExpect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableExpect::Rows(rows) => f.debug_tuple("Rows").field(rows).finish(),
+            TableExpect::Contains(row) => f.debug_tuple("Contains").field(row).finish(),
+            TableExpect::Column(column, _) => f.debug_tuple("Column").field(column).finish(),
+        }
+    }
+}
+
+impl TableExpect {
+    /// Returns failure description when the rows don't meet the assertion.
+    pub(crate) fn check(&self, rows: &[Value]) -> Option<String> {
+        match self {
+            TableExpect::Rows(expected) => {
+                if expected.len() == rows.len() && matching(expected, rows) {
+                    None
+                } else {
+                    Some(format!("expect rows {:?} but {:?}", expected, rows))
+                }
+            },
+            TableExpect::Contains(expected) => {
+                if rows.iter().any(|r| row_matches(expected, r)) {
+                    None
+                } else {
+                    Some(format!("expect row {:?} but not found in {:?}", expected, rows))
+                }
+            },
+            TableExpect::Column(column, f) => {
+                for (idx, r) in rows.iter().enumerate() {
+                    let v = match r {
+                        Value::Object(m) => m.get(column),
+                        _ => None
+                    };
+                    match v {
+                        Some(v) if f(v) => (),
+                        Some(v) => return Some(format!("column {} of row {} does not match: {:?}", column, idx, v)),
+                        None => return Some(format!("column {} not found", column)),
+                    }
+                }
+                None
+            },
+        }
+    }
+}
+
+/// Rows of the result set, each row is an object of column name and value of inferred type.
+pub(crate) fn rows<R: CaptureRow>(rows: &[R]) -> Result<Vec<Value>, String> {
+    let mut table = vec![];
+    for r in rows {
+        match r.object() {
+            Ok(v) => table.push(v),
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(table)
+}
+
+/// Columns of expected row all equal to the row, other columns of the row are ignored.
+fn row_matches(expected: &Value, row: &Value) -> bool {
+    match (expected, row) {
+        (Value::Object(e), Value::Object(r)) => e.iter().all(|(k, v)| r.get(k).is_some_and(|a| same_value(v, a))),
+        _ => same_value(expected, row),
+    }
+}
+
+/// Integers of different types are equal if their values are, other values compare by `Value::loose_eq`.
+fn same_value(expected: &Value, actual: &Value) -> bool {
+    match (integer(expected), integer(actual)) {
+        (Some(e), Some(a)) => e == a,
+        _ => expected.loose_eq(actual),
+    }
+}
+
+fn integer(v: &Value) -> Option<i128> {
+    match v {
+        Value::I32(i) => Some(i128::from(*i)),
+        Value::U32(u) => Some(i128::from(*u)),
+        Value::I64(i) => Some(i128::from(*i)),
+        Value::U64(u) => Some(i128::from(*u)),
+        _ => None,
+    }
+}
+
+/// Whether every expected row matches a distinct row, by augmenting paths of bipartite matching.
+fn matching(expected: &[Value], rows: &[Value]) -> bool {
+    fn assign(e: usize, expected: &[Value], rows: &[Value], owner: &mut [Option<usize>], seen: &mut [bool]) -> bool {
+        for (r, row) in rows.iter().enumerate() {
+            if seen[r] || !row_matches(&expected[e], row) {
+                continue;
+            }
+            seen[r] = true;
+            let free = match owner[r] {
+                Some(o) => assign(o, expected, rows, owner, seen),
+                None => true
+            };
+            if free {
+                owner[r] = Some(e);
+                return true;
+            }
+        }
+        false
+    }
+    let mut owner = vec![None; rows.len()];
+    (0..expected.len()).all(|e| assign(e, expected, rows, &mut owner, &mut vec![false; rows.len()]))
+}
@@ -0,0 +1,411 @@
+use std::{collections::{BTreeMap, HashMap}, str::FromStr, time::Duration};
+
+use log::debug;
+use prost::Message as _;
+use prost_reflect::{prost_types::FileDescriptorProto, DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor, SerializeOptions};
+use tonic::{
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    metadata::{MetadataKey, MetadataMap, MetadataValue},
+    transport::{Channel, Endpoint},
+};
+use tonic_reflection::pb::{
+    server_reflection_client::ServerReflectionClient,
+    server_reflection_request::MessageRequest,
+    server_reflection_response::MessageResponse,
+    ServerReflectionRequest,
+};
+
+use crate::{capture::{self, Capture}, context::Context, report::ReportItem, value::Value};
+
+/// Where method descriptors come from.
+#[derive(Debug, Clone)]
+pub enum Schema<'a> {
+    /// Encoded `FileDescriptorSet` file, e.g. `protoc --include_imports -o api.pb api.proto`.
+    DescriptorSet(&'a str),
+    /// Server reflection (`grpc.reflection.v1alpha`).
+    Reflection,
+}
+
+/// A gRPC task calling an unary or server-streaming method.
+pub struct GrpcTask<'a> {
+    pub(crate) config: GrpcTaskBuilder<'a>,
+    pub(crate) task_id: String,
+}
+
+impl GrpcTask<'_> {
+    pub async fn execute(&mut self, ctx: &mut Context) -> crate::Result<()> {
+        let (service, method) = match self.config.method.rsplit_once(['/', '.']) {
+            Some(m) => m,
+            None => return Err(crate::error::parse_value(format!("invalid gRPC method '{}'", self.config.method).into()))
+        };
+        let endpoint = match Endpoint::from_shared(self.config.url.to_string()) {
+            Ok(e) => e.connect_timeout(self.config.connect_timeout),
+            Err(e) => return Err(crate::error::parse_value(e.into()))
+        };
+        let channel = match endpoint.connect().await {
+            Ok(c) => c,
+            Err(e) => return Err(crate::error::create_client(e.into()))
+        };
+        let pool = match self.config.schema {
+            Schema::DescriptorSet(path) => match std::fs::read(path) {
+                Ok(b) => match DescriptorPool::decode(b.as_slice()) {
+                    Ok(p) => p,
+                    Err(e) => return Err(crate::error::decode(e.into()))
+                },
+                Err(e) => return Err(crate::error::io(e.into()))
+            },
+            Schema::Reflection => match reflect(channel.clone(), service).await {
+                Ok(p) => p,
+                Err(e) => return Err(e)
+            },
+        };
+        let method = match pool.get_service_by_name(service).and_then(|s| s.methods().find(|m| m.name() == method)) {
+            Some(m) => m,
+            None => return Err(crate::error::parse_value(format!("gRPC method '{}' not found", self.config.method).into()))
+        };
+        if method.is_client_streaming() {
+            return Err(crate::error::unimplement("client streaming gRPC method"));
+        }
+
+        let message = match serde_json::to_value(self.config.message.resolve(&ctx.store)) {
+            Ok(v) => match DynamicMessage::deserialize(method.input(), v) {
+                Ok(m) => m,
+                Err(e) => return Err(crate::error::parse_value(e.into()))
+            },
+            Err(e) => return Err(crate::error::parse_value(e.into()))
+        };
+        let mut request = tonic::Request::new(message);
+        if let Some(ref m) = self.config.metadata {
+            for (k, v) in m {
+                let key = match MetadataKey::from_str(k) {
+                    Ok(k) => k,
+                    Err(e) => return Err(crate::error::parse_value(e.into()))
+                };
+                let value = match MetadataValue::try_from(v.resolve(&ctx.store).as_str()) {
+                    Ok(v) => v,
+                    Err(e) => return Err(crate::error::parse_value(e.into()))
+                };
+                request.metadata_mut().insert(key, value);
+            }
+        }
+
+        let task_id = self.task_id.to_owned();
+        let target = format!("{}/{}", self.config.url, self.config.method);
+        let call = self.call(ctx, channel, &method, request);
+        let status = match tokio::time::timeout(self.config.timeout, call).await {
+            Ok(Ok(s)) => s,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => {
+                ctx.report(ReportItem::failed(&task_id, format!("{} (timeout)", target)));
+                return Ok(());
+            }
+        };
+        let expect_code = self.config.expect_code.unwrap_or(tonic::Code::Ok as i32);
+        let report_item = match status {
+            Some(s) if s.code() as i32 != expect_code => {
+                ReportItem::failed(&task_id, format!("{} (status:{:?} {})", target, s.code(), s.message()))
+            }
+            None if expect_code != tonic::Code::Ok as i32 => {
+                ReportItem::failed(&task_id, format!("{} (expect status {} but Ok)", target, expect_code))
+            }
+            _ => match &self.config.expect {
+                Some(_expect) => super::check_expect(&task_id, &target, _expect, ctx),
+                None => ReportItem::success(&task_id, format!("{} succeed", target)),
+            }
+        };
+        ctx.report(report_item);
+        Ok(())
+    }
+
+    /// Call method and capture responses, returns error status of the call.
+    async fn call(&self, ctx: &mut Context, channel: Channel, method: &MethodDescriptor, request: tonic::Request<DynamicMessage>) -> crate::Result<Option<tonic::Status>> {
+        let mut grpc = tonic::client::Grpc::new(channel);
+        if let Err(e) = grpc.ready().await {
+            return Err(crate::error::request(e.into()));
+        }
+        let path = match tonic::codegen::http::uri::PathAndQuery::from_str(&format!("/{}/{}", method.parent_service().full_name(), method.name())) {
+            Ok(p) => p,
+            Err(e) => return Err(crate::error::parse_value(e.into()))
+        };
+        let codec = DynamicCodec(method.output());
+        if !method.is_server_streaming() {
+            let rsp = match grpc.unary(request, path, codec).await {
+                Ok(r) => r,
+                Err(s) => return Ok(Some(s))
+            };
+            self.capture_metadata(ctx, rsp.metadata());
+            return match self.capture(ctx, rsp.get_ref()) {
+                Ok(_) => Ok(None),
+                Err(e) => Err(e)
+            };
+        }
+
+        let rsp = match grpc.server_streaming(request, path, codec).await {
+            Ok(r) => r,
+            Err(s) => return Ok(Some(s))
+        };
+        self.capture_metadata(ctx, rsp.metadata());
+        let mut stream = rsp.into_inner();
+        let mut count = 0;
+        loop {
+            if self.config.max_messages.map(|m| count >= m).unwrap_or(false) {
+                break;
+            }
+            let message = match stream.message().await {
+                Ok(Some(m)) => m,
+                Ok(None) => break,
+                Err(s) => return Ok(Some(s))
+            };
+            debug!("grpc stream message {}: {:?}", count, message);
+            count += 1;
+            self.capture(ctx, &message)?;
+        }
+        if let Some(count_key) = self.config.count_key {
+            ctx.store.set(count_key.to_string(), Value::U64(count as u64));
+        }
+        Ok(None)
+    }
+
+    fn capture_metadata(&self, ctx: &mut Context, metadata: &MetadataMap) {
+        if let Some(ref caps) = self.config.capture {
+            for _cap in caps.iter().filter(|c| c.is_header()) {
+                if let Capture::Header(ref _c) = _cap {
+                    if let Some(v) = metadata.get(_c.key).and_then(|v| v.to_str().ok()) {
+                        ctx.store.set(_c.save_key.to_owned(), Value::Str(v.to_string()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn capture(&self, ctx: &mut Context, message: &DynamicMessage) -> crate::Result<()> {
+        if let Some(ref caps) = self.config.capture {
+            let options = SerializeOptions::new()
+                .use_proto_field_name(true)
+                .skip_default_fields(false)
+                .stringify_64_bit_integers(false);
+            let json = match message.serialize_with_options(serde_json::value::Serializer, &options) {
+                Ok(j) => j,
+                Err(e) => return Err(crate::error::decode(e.into()))
+            };
+            capture::save_json(&caps.iter().filter(|c| c.is_json()).collect::<Vec<_>>(), &json, &mut ctx.store);
+        }
+        Ok(())
+    }
+}
+
+/// Fetch descriptors of service and its dependencies by server reflection.
+async fn reflect(channel: Channel, service: &str) -> crate::Result<DescriptorPool> {
+    let mut client = ServerReflectionClient::new(channel);
+    let mut files: HashMap<String, FileDescriptorProto> = HashMap::new();
+    let mut requests = vec![MessageRequest::FileContainingSymbol(service.to_string())];
+    while let Some(message_request) = requests.pop() {
+        let request = ServerReflectionRequest { host: String::new(), message_request: Some(message_request) };
+        let mut stream = match client.server_reflection_info(futures_util::stream::iter(vec![request])).await {
+            Ok(r) => r.into_inner(),
+            Err(e) => return Err(crate::error::request(e.into()))
+        };
+        let rsp = match stream.message().await {
+            Ok(Some(r)) => r,
+            Ok(None) => return Err(crate::error::request("empty reflection response".into())),
+            Err(e) => return Err(crate::error::request(e.into()))
+        };
+        let protos = match rsp.message_response {
+            Some(MessageResponse::FileDescriptorResponse(f)) => f.file_descriptor_proto,
+            Some(MessageResponse::ErrorResponse(e)) => {
+                return Err(crate::error::request(format!("reflection error {}: {}", e.error_code, e.error_message).into()))
+            }
+            _ => return Err(crate::error::request("unexpected reflection response".into())),
+        };
+        for b in protos {
+            let file = match FileDescriptorProto::decode(b.as_slice()) {
+                Ok(f) => f,
+                Err(e) => return Err(crate::error::decode(e.into()))
+            };
+            for dep in &file.dependency {
+                if !files.contains_key(dep) && file.name() != dep {
+                    requests.push(MessageRequest::FileByFilename(dep.to_owned()));
+                }
+            }
+            files.insert(file.name().to_string(), file);
+        }
+        requests.retain(|r| !matches!(r, MessageRequest::FileByFilename(f) if files.contains_key(f)));
+    }
+    let mut pool = DescriptorPool::new();
+    match pool.add_file_descriptor_protos(files.into_values()) {
+        Ok(_) => Ok(pool),
+        Err(e) => Err(crate::error::decode(e.into()))
+    }
+}
+
+/// Encodes request and decodes response messages with runtime descriptors.
+struct DynamicCodec(MessageDescriptor);
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicCodec;
+    type Decoder = DynamicCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicCodec(self.0.clone())
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicCodec(self.0.clone())
+    }
+}
+
+impl Encoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = tonic::Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst).map_err(|e| tonic::Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = tonic::Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        match DynamicMessage::decode(self.0.clone(), src) {
+            Ok(m) => Ok(Some(m)),
+            Err(e) => Err(tonic::Status::internal(e.to_string()))
+        }
+    }
+}
+
+/// gRPC task builder.
+/// # Example
+///
+/// ```
+/// use anole::{task::grpc::{GrpcTaskBuilder, Schema}, capture, value::Value};
+///
+/// let grpc_task = GrpcTaskBuilder::new()
+///     .url("http://127.0.0.1:50051")
+///     .method("shop.v1.OrderService/GetOrder")
+///     .schema(Schema::DescriptorSet("protos/shop.pb"))
+///     .metadata(("authorization", Value::from(":token")))
+///     .message(Value::object([("order_id", ":order_id")]))
+///     .capture(vec![capture::json("order.items|0.sku", "sku")])
+///     .build();
+/// ```
+pub struct GrpcTaskBuilder<'a> {
+    pub(crate) url: &'a str,
+    pub(crate) method: &'a str,
+    pub(crate) schema: Schema<'a>,
+    pub(crate) metadata: Option<HashMap<&'a str, Value>>,
+    pub(crate) message: Value,
+    pub(crate) capture: Option<Vec<Capture<'a>>>,
+    pub(crate) max_messages: Option<usize>,
+    pub(crate) count_key: Option<&'a str>,
+    pub(crate) connect_timeout: Duration,
+    pub(crate) timeout: Duration,
+    pub(crate) expect_code: Option<i32>,
+    pub(crate) expect: Option<(&'a str, Value)>,
+}
+
+impl<'a> GrpcTaskBuilder<'a> {
+    /// Create an builder.
+    pub fn new() -> Self {
+        GrpcTaskBuilder {
+            url: "",
+            method: "",
+            schema: Schema::Reflection,
+            metadata: None,
+            message: Value::Object(BTreeMap::new()),
+            capture: None,
+            max_messages: None,
+            count_key: None,
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(30),
+            expect_code: None,
+            expect: None,
+        }
+    }
+
+    /// Add server url, e.g. `http://127.0.0.1:50051`.
+    pub fn url(mut self, url: &'a str) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// Full method name, e.g. `package.Service/Method`.
+    pub fn method(mut self, method: &'a str) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Specify where descriptors come from, default is `Schema::Reflection`.
+    pub fn schema(mut self, schema: Schema<'a>) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    /// Add request metadata.
+    pub fn metadata(mut self, metadata: (&'a str, Value)) -> Self {
+        self.metadata.get_or_insert(HashMap::new()).insert(metadata.0, metadata.1);
+        self
+    }
+
+    /// Request message in protobuf JSON mapping, wildcards in the tree are replaced with store values.
+    pub fn message(mut self, message: Value) -> Self {
+        self.message = message;
+        self
+    }
+
+    /// Add captures, JSON captures use proto field names and header captures read response metadata.
+    /// For server-streaming methods, captures apply to every message and later messages overwrite earlier values.
+    pub fn capture(mut self, capture: Vec<Capture<'a>>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Stop reading server stream after given messages.
+    pub fn max_messages(mut self, count: usize) -> Self {
+        self.max_messages = Some(count);
+        self
+    }
+
+    /// Save received stream message count into store.
+    pub fn count(mut self, save_key: &'a str) -> Self {
+        self.count_key = Some(save_key);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Maximum time of the call including reading stream, default is 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Specify expected status code, e.g. `5` for `NOT_FOUND`, default is `0` (`OK`).
+    pub fn expect_code(mut self, code: i32) -> Self {
+        self.expect_code = Some(code);
+        self
+    }
+
+    /// Specify an expect condition for task.
+    pub fn expect(mut self, tup: (&'a str, Value)) -> Self {
+        self.expect = Some(tup);
+        self
+    }
+
+    /// Build an GrpcTask use this builder.
+    pub fn build(self) -> GrpcTask<'a> {
+        GrpcTask { config: self, task_id: crate::faker::uuid_v4() }
+    }
+}
+
+impl Default for GrpcTaskBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,247 @@
+use std::time::{Duration, Instant};
+
+use log::debug;
+use reqwest::{Client, RequestBuilder};
+
+use crate::{context::Context, value::Value};
+
+/// Token is treated as expired this long before `expires_in` elapses.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(10);
+
+/// HTTP authentication, values support wildcard to use store value.
+/// # Example
+///
+/// ```
+/// use anole::task::http::{HttpTaskBuilder, auth::{Auth, OAuth2}};
+///
+/// let http_task = HttpTaskBuilder::new()
+///     .url("https://example.com/v1/me")
+///     .auth(Auth::bearer(":token"))
+///     .build();
+///
+/// let http_task = HttpTaskBuilder::new()
+///     .url("https://example.com/v1/orders")
+///     .auth(Auth::oauth2(OAuth2::client_credentials("https://example.com/oauth/token", "id", "secret").scope("orders")))
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub enum Auth {
+    Basic { username: Value, password: Option<Value> },
+    Bearer(Value),
+    ApiKey { name: String, value: Value, location: ApiKeyLocation },
+    OAuth2(OAuth2),
+}
+
+/// Where to put API key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+}
+
+impl Auth {
+    pub fn basic<U: Into<Value>, P: Into<Value>>(username: U, password: P) -> Self {
+        Auth::Basic { username: username.into(), password: Some(password.into()) }
+    }
+
+    pub fn bearer<T: Into<Value>>(token: T) -> Self {
+        Auth::Bearer(token.into())
+    }
+
+    pub fn api_key_header<V: Into<Value>>(name: &str, value: V) -> Self {
+        Auth::ApiKey { name: name.to_string(), value: value.into(), location: ApiKeyLocation::Header }
+    }
+
+    pub fn api_key_query<V: Into<Value>>(name: &str, value: V) -> Self {
+        Auth::ApiKey { name: name.to_string(), value: value.into(), location: ApiKeyLocation::Query }
+    }
+
+    pub fn oauth2(o: OAuth2) -> Self {
+        Auth::OAuth2(o)
+    }
+
+    /// Whether a new credential can be fetched when server responds 401.
+    pub(crate) fn is_refreshable(&self) -> bool {
+        matches!(self, Auth::OAuth2(_))
+    }
+
+    /// Apply credential to request, `refresh` forces fetching a new OAuth2 token.
+    pub(crate) async fn apply(&self, ctx: &mut Context, client: &Client, rb: RequestBuilder, refresh: bool) -> crate::Result<RequestBuilder> {
+        match self {
+            Auth::Basic { username, password } => {
+                let password = password.as_ref().map(|p| p.resolve(&ctx.store).as_str());
+                Ok(rb.basic_auth(username.resolve(&ctx.store).as_str(), password))
+            }
+            Auth::Bearer(token) => Ok(rb.bearer_auth(token.resolve(&ctx.store).as_str())),
+            Auth::ApiKey { name, value, location } => {
+                let value = value.resolve(&ctx.store).as_str();
+                match location {
+                    ApiKeyLocation::Header => Ok(rb.header(name.as_str(), value)),
+                    ApiKeyLocation::Query => Ok(rb.query(&[(name.as_str(), value)])),
+                }
+            }
+            Auth::OAuth2(o) => {
+                match o.token(ctx, client, refresh).await {
+                    Ok(t) => Ok(rb.bearer_auth(t)),
+                    Err(e) => Err(e)
+                }
+            }
+        }
+    }
+}
+
+/// OAuth2 grant type.
+#[derive(Debug, Clone)]
+pub enum Grant {
+    ClientCredentials,
+    Password { username: Value, password: Value },
+}
+
+/// OAuth2 token fetching options, tokens are cached during engine run.
+#[derive(Debug, Clone)]
+pub struct OAuth2 {
+    pub token_url: String,
+    pub client_id: Value,
+    pub client_secret: Value,
+    pub grant: Grant,
+    pub scope: Option<String>,
+    pub save_key: Option<String>,
+}
+
+impl OAuth2 {
+    pub fn client_credentials<I: Into<Value>, S: Into<Value>>(token_url: &str, client_id: I, client_secret: S) -> Self {
+        OAuth2 {
+            token_url: token_url.to_string(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            grant: Grant::ClientCredentials,
+            scope: None,
+            save_key: None,
+        }
+    }
+
+    pub fn password<I, S, U, P>(token_url: &str, client_id: I, client_secret: S, username: U, password: P) -> Self
+    where I: Into<Value>, S: Into<Value>, U: Into<Value>, P: Into<Value> {
+        OAuth2 {
+            grant: Grant::Password { username: username.into(), password: password.into() },
+            ..Self::client_credentials(token_url, client_id, client_secret)
+        }
+    }
+
+    /// Specify requested scope.
+    pub fn scope(mut self, scope: &str) -> Self {
+        self.scope = Some(scope.to_string());
+        self
+    }
+
+    /// Save fetched access token into store.
+    pub fn save_key(mut self, key: &str) -> Self {
+        self.save_key = Some(key.to_string());
+        self
+    }
+
+    fn cache_key(&self, ctx: &Context) -> String {
+        let user = match &self.grant {
+            Grant::ClientCredentials => String::new(),
+            Grant::Password { username, .. } => username.resolve(&ctx.store).as_str(),
+        };
+        format!("{}|{}|{}|{}", self.token_url, self.client_id.resolve(&ctx.store).as_str(), user, self.scope.as_deref().unwrap_or(""))
+    }
+
+    async fn token(&self, ctx: &mut Context, client: &Client, refresh: bool) -> crate::Result<String> {
+        let cache_key = self.cache_key(ctx);
+        let cached = ctx.oauth2_tokens.remove(&cache_key);
+        if let Some(ref t) = cached {
+            if !refresh && !t.is_expired() {
+                let access_token = t.access_token.to_owned();
+                ctx.oauth2_tokens.insert(cache_key, t.to_owned());
+                return Ok(access_token);
+            }
+        }
+
+        let mut token = None;
+        if let Some(refresh_token) = cached.and_then(|t| t.refresh_token) {
+            match self.request_token(ctx, client, Some(refresh_token.to_owned())).await {
+                // a refresh response without refresh token keeps the old one usable.
+                Ok(t) => token = Some(Token { refresh_token: t.refresh_token.or(Some(refresh_token)), ..t }),
+                Err(e) => debug!("refresh oauth2 token failed: {:?}", e)
+            }
+        }
+        let token = match token {
+            Some(t) => t,
+            None => match self.request_token(ctx, client, None).await {
+                Ok(t) => t,
+                Err(e) => return Err(e)
+            }
+        };
+        let access_token = token.access_token.to_owned();
+        ctx.oauth2_tokens.insert(cache_key, token);
+        if let Some(ref save_key) = self.save_key {
+            ctx.store.set(save_key.to_owned(), Value::Str(access_token.to_owned()));
+        }
+        Ok(access_token)
+    }
+
+    async fn request_token(&self, ctx: &Context, client: &Client, refresh_token: Option<String>) -> crate::Result<Token> {
+        let mut form: Vec<(&str, String)> = vec![
+            ("client_id", self.client_id.resolve(&ctx.store).as_str()),
+            ("client_secret", self.client_secret.resolve(&ctx.store).as_str()),
+        ];
+        match refresh_token {
+            Some(refresh_token) => {
+                form.push(("grant_type", "refresh_token".to_string()));
+                form.push(("refresh_token", refresh_token));
+            }
+            None => match &self.grant {
+                Grant::ClientCredentials => form.push(("grant_type", "client_credentials".to_string())),
+                Grant::Password { username, password } => {
+                    form.push(("grant_type", "password".to_string()));
+                    form.push(("username", username.resolve(&ctx.store).as_str()));
+                    form.push(("password", password.resolve(&ctx.store).as_str()));
+                }
+            }
+        }
+        if let Some(ref scope) = self.scope {
+            form.push(("scope", scope.to_owned()));
+        }
+
+        debug!("fetch oauth2 token from {}", self.token_url);
+        let rsp = match client.post(&self.token_url).form(&form).send().await {
+            Ok(r) => r,
+            Err(e) => return Err(crate::error::request(e.into()))
+        };
+        if !rsp.status().is_success() {
+            return Err(crate::error::request(format!("fetch oauth2 token failed (status_code:{})", rsp.status().as_u16()).into()));
+        }
+        let body = match rsp.json::<serde_json::Value>().await {
+            Ok(b) => b,
+            Err(e) => return Err(crate::error::decode(e.into()))
+        };
+        let access_token = match body["access_token"].as_str() {
+            Some(t) => t.to_string(),
+            None => return Err(crate::error::decode("access_token not found".into()))
+        };
+        Ok(Token {
+            access_token,
+            refresh_token: body["refresh_token"].as_str().map(|t| t.to_string()),
+            expires_at: body["expires_in"].as_u64().map(|s| Instant::now() + Duration::from_secs(s)),
+        })
+    }
+}
+
+/// Cached OAuth2 token.
+#[derive(Debug, Clone)]
+pub(crate) struct Token {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Option<Instant>,
+}
+
+impl Token {
+    fn is_expired(&self) -> bool {
+        match self.expires_at {
+            Some(t) => Instant::now() + EXPIRY_MARGIN >= t,
+            None => false,
+        }
+    }
+}
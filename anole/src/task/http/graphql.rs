@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use reqwest::Response;
+
+use crate::{capture::{self, Capture}, context::Context, value::{Body, Value}};
+
+enum Document {
+    Inline(String),
+    File(String),
+}
+
+enum ErrorsExpect {
+    /// `errors` must be absent or empty.
+    Absent,
+    /// `errors` must contain every code in `extensions.code`.
+    Codes(Vec<String>),
+    /// `errors` is not checked.
+    Any,
+}
+
+/// GraphQL request, sent as JSON `POST` body by `HttpTaskBuilder::graphql`.
+/// Response fails when `errors` is present unless expected.
+/// # Example
+///
+/// ```
+/// use anole::{task::http::{HttpTaskBuilder, graphql::GraphQL}, capture, value::Value};
+///
+/// let http_task = HttpTaskBuilder::new()
+///     .url("https://example.com/graphql")
+///     .graphql(GraphQL::query("mutation Create($sku: String!) { createOrder(sku: $sku) { id } }")
+///         .operation_name("Create")
+///         .variable("sku", ":sku")
+///         .capture(vec![capture::json("createOrder.id", "order_id")]))
+///     .build();
+///
+/// let http_task = HttpTaskBuilder::new()
+///     .url("https://example.com/graphql")
+///     .graphql(GraphQL::file("queries/order.graphql")
+///         .variable("id", Value::from(":order_id"))
+///         .expect_error_code("NOT_FOUND"))
+///     .build();
+/// ```
+pub struct GraphQL<'a> {
+    document: Document,
+    operation_name: Option<&'a str>,
+    variables: BTreeMap<String, Value>,
+    capture: Vec<Capture<'a>>,
+    save_data: Option<&'a str>,
+    errors: ErrorsExpect,
+}
+
+impl<'a> GraphQL<'a> {
+    fn new(document: Document) -> Self {
+        GraphQL {
+            document,
+            operation_name: None,
+            variables: BTreeMap::new(),
+            capture: vec![],
+            save_data: None,
+            errors: ErrorsExpect::Absent,
+        }
+    }
+
+    /// Inline query or mutation document.
+    pub fn query(document: &str) -> Self {
+        Self::new(Document::Inline(document.to_string()))
+    }
+
+    /// Read query or mutation document from file when task executes.
+    pub fn file(path: &str) -> Self {
+        Self::new(Document::File(path.to_string()))
+    }
+
+    pub fn operation_name(mut self, name: &'a str) -> Self {
+        self.operation_name = Some(name);
+        self
+    }
+
+    /// Add a variable, wildcards are replaced with store values.
+    pub fn variable<V: Into<Value>>(mut self, name: &str, v: V) -> Self {
+        self.variables.insert(name.to_string(), v.into());
+        self
+    }
+
+    /// Capture from `data`, capture keys are relative to it, e.g. `createOrder.id`.
+    pub fn capture(mut self, caps: Vec<Capture<'a>>) -> Self {
+        self.capture = caps;
+        self
+    }
+
+    /// Save whole `data` object into store, nested values can be used by wildcard path like `:order.createOrder.id`.
+    pub fn save_data(mut self, save_key: &'a str) -> Self {
+        self.save_data = Some(save_key);
+        self
+    }
+
+    /// Expect `errors` contains an error with `extensions.code`, may be called several times.
+    pub fn expect_error_code(mut self, code: &str) -> Self {
+        match self.errors {
+            ErrorsExpect::Codes(ref mut codes) => codes.push(code.to_string()),
+            _ => self.errors = ErrorsExpect::Codes(vec![code.to_string()]),
+        }
+        self
+    }
+
+    /// Do not check `errors`, e.g. for partial data responses.
+    pub fn allow_errors(mut self) -> Self {
+        self.errors = ErrorsExpect::Any;
+        self
+    }
+
+    pub(crate) fn body(&self) -> crate::Result<Body> {
+        let document = match &self.document {
+            Document::Inline(d) => d.to_owned(),
+            Document::File(path) => match std::fs::read_to_string(path) {
+                Ok(d) => d,
+                Err(e) => return Err(crate::error::io(e.into()))
+            },
+        };
+        let mut body = BTreeMap::new();
+        body.insert("query".to_string(), Value::Str(document));
+        if !self.variables.is_empty() {
+            body.insert("variables".to_string(), Value::Object(self.variables.to_owned()));
+        }
+        if let Some(name) = self.operation_name {
+            body.insert("operationName".to_string(), Value::Str(name.to_string()));
+        }
+        Ok(Body::Json(Value::Object(body)))
+    }
+
+    /// Read GraphQL response, returns failure description when `errors` is not as expected.
+    pub(crate) async fn read(&self, ctx: &mut Context, rsp: Response) -> crate::Result<Option<String>> {
+        let json = match rsp.json::<serde_json::Value>().await {
+            Ok(j) => j,
+            Err(e) => return Err(crate::error::decode(e.into()))
+        };
+        let errors = json["errors"].as_array().map(|e| e.to_owned()).unwrap_or_default();
+        match &self.errors {
+            ErrorsExpect::Absent if !errors.is_empty() => {
+                let messages = errors.iter().filter_map(|e| e["message"].as_str()).collect::<Vec<_>>();
+                return Ok(Some(format!("graphql errors {:?}", messages)));
+            }
+            ErrorsExpect::Codes(codes) => {
+                let actual = errors.iter().filter_map(|e| e["extensions"]["code"].as_str()).collect::<Vec<_>>();
+                if let Some(missing) = codes.iter().find(|c| !actual.contains(&c.as_str())) {
+                    return Ok(Some(format!("expect graphql error code '{}' but {:?}", missing, actual)));
+                }
+            }
+            _ => (),
+        }
+
+        let data = &json["data"];
+        capture::save_json(&self.capture.iter().collect::<Vec<_>>(), data, &mut ctx.store);
+        if let Some(save_key) = self.save_data {
+            if !data.is_null() {
+                ctx.store.set(save_key.to_string(), Value::from(data));
+            }
+        }
+        Ok(None)
+    }
+}
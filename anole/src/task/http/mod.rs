@@ -0,0 +1,717 @@
+use std::{collections::HashMap, sync::Arc};
+use reqwest::{Response, RequestBuilder};
+
+use crate::{value::{Value, Body, Part}, capture::{self, Capture}, context::Context, de::xml, report::ReportItem, tls::TlsOption};
+
+use self::{session::{ClientOverride, HttpSessionOption, HttpVersion, RedirectPolicy, DEFAULT_SESSION}, auth::Auth, sign::{Signer, CanonicalRequest}, sse::Sse, graphql::GraphQL};
+
+pub mod session;
+pub mod auth;
+pub mod sign;
+pub mod sse;
+pub mod graphql;
+
+/// HTTP Methods.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Method {
+    Get,
+    Post,
+    Head,
+    Put,
+    Delete,
+    Patch,
+    Options,
+    Trace,
+    Connect,
+    /// Custom verb created by `Method::custom`.
+    Custom(reqwest::Method),
+}
+
+/// HTTP body deserializer
+#[derive(Debug, Clone, PartialEq)]
+pub enum Deserializer {
+    /// Pick by response `Content-Type`, sniff body when absent or ambiguous.
+    Auto,
+    /// Json
+    Json,
+    /// XML
+    Xml,
+    /// HTML, captured with the XML capture syntax by the XML parser.
+    /// Only XHTML parses fully, in other HTML elements after an end tag closing unclosed ones,
+    /// like `</div>` of `<div><br></div>`, are not captured.
+    Html,
+    /// Plain text, body captures are skipped.
+    Text,
+    /// Protobuf (Not support yet).
+    Protobuf(String),
+}
+
+impl Deserializer {
+    pub fn json(&self, b: &str) -> crate::Result<serde_json::Value> {
+        match serde_json::from_str::<serde_json::Value>(b) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(crate::error::decode(e.into()))
+        }
+    }
+
+    pub fn xml(&self) -> Option<&[u8]> {
+        None
+    }
+
+    pub fn is_json(&self) -> bool {
+        matches!(self, Deserializer::Json)
+    }
+
+    pub fn is_xml(&self) -> bool {
+        matches!(self, Deserializer::Xml)
+    }
+
+    pub fn is_html(&self) -> bool {
+        matches!(self, Deserializer::Html)
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self, Deserializer::Text)
+    }
+
+    pub fn is_pb(&self) -> bool {
+        matches!(self, Deserializer::Protobuf(_))
+    }
+
+    pub fn is_auto(&self) -> bool {
+        matches!(self, Deserializer::Auto)
+    }
+
+    /// Resolve `Auto` into a concrete deserializer using the response `Content-Type` and body.
+    /// Other deserializers are returned as is.
+    pub fn resolve(&self, content_type: Option<&str>, body: &str) -> Deserializer {
+        if !self.is_auto() {
+            return self.clone();
+        }
+        if let Some(ct) = content_type {
+            let mime = ct.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+            if mime.ends_with("/json") || mime.ends_with("+json") {
+                return Deserializer::Json;
+            } else if mime == "text/html" || mime == "application/xhtml+xml" {
+                return Deserializer::Html;
+            } else if mime.ends_with("/xml") || mime.ends_with("+xml") {
+                return Deserializer::Xml;
+            }
+        }
+        Self::sniff(body)
+    }
+
+    fn sniff(body: &str) -> Deserializer {
+        let trimmed = body.trim_start();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<serde_json::Value>(trimmed).is_ok() {
+            return Deserializer::Json;
+        }
+        if trimmed.starts_with('<') {
+            let head = trimmed.chars().take(256).collect::<String>().to_ascii_lowercase();
+            if head.starts_with("<!doctype html") || head.contains("<html") {
+                return Deserializer::Html;
+            }
+            return Deserializer::Xml;
+        }
+        Deserializer::Text
+    }
+}
+
+impl Method {
+    /// Custom verb, e.g. `PURGE`, `REPORT`. Returns Err if it is not a valid method token.
+    pub fn custom(name: &str) -> crate::Result<Self> {
+        match reqwest::Method::from_bytes(name.as_bytes()) {
+            Ok(m) => Ok(Method::Custom(m)),
+            Err(e) => Err(crate::error::parse_value(e.into()))
+        }
+    }
+}
+
+impl From<Method> for reqwest::Method {
+    fn from(val: Method) -> Self {
+        reqwest::Method::from(&val)
+    }
+}
+
+impl From<&Method> for reqwest::Method {
+    fn from(val: &Method) -> Self {
+        match val {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Head => reqwest::Method::HEAD,
+            Method::Delete => reqwest::Method::DELETE,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Options => reqwest::Method::OPTIONS,
+            Method::Trace => reqwest::Method::TRACE,
+            Method::Connect => reqwest::Method::CONNECT,
+            Method::Custom(m) => m.to_owned(),
+        }
+    }
+}
+
+/// An HTTP task.
+pub struct HttpTask<'a> {
+    pub(crate) config: HttpTaskBuilder<'a>,
+    pub(crate) task_id: String,
+}
+
+
+impl HttpTask<'_> {
+    pub async fn execute(&mut self, ctx: &mut Context) -> crate::Result<()> {
+        let overrides = self.config.client_override();
+        let client = if self.config.share_session {
+            ctx.http_client(self.config.session, &overrides)
+        } else {
+            overrides.apply(HttpSessionOption::builder().cookie_store(false)).build_client(None)
+        };
+        let client = match client {
+            Ok(c) => c,
+            Err(e) => return Err(e)
+        };
+
+        let mut url = match url::Url::parse(self.config.url) {
+            Ok(u) => u,
+            Err(e) => return Err(crate::error::parse_value(e.into()))
+        };
+        if let Err(p) = super::replace_path(&mut url, &ctx.store) {
+            ctx.report(ReportItem::failed(&self.task_id.to_owned(), format!("{} (not found '{}' value)", url, p)));
+            return Ok(());
+        }
+        let method = reqwest::Method::from(&self.config.method);
+        let mut request_builder = client.request(method, url.as_str());
+
+        //header
+        if let Some(h) = &self.config.header {
+            let mut h = h.to_owned();
+            for (k, v) in h.iter_mut() {
+                if let Some(wildcard) = v.as_wildcard() {
+                    if let Some(wv) = ctx.store.get(wildcard) {
+                        *v = wv.to_owned();
+                    }
+                }
+                request_builder = request_builder.header(*k, v.as_str());
+            }
+        }
+
+        //query
+        if let Some(q) = &self.config.query {
+            let mut q = q.to_owned();
+            for (_, v) in q.iter_mut() {
+                if let Some(wildcard) = v.as_wildcard() {
+                    if let Some(wv) = ctx.store.get(wildcard) {
+                        *v = wv.to_owned();
+                    }
+                }
+            }
+            request_builder = request_builder.query(&q);
+        }
+        //form
+        if let Some(f) = &self.config.form {
+            let mut f = f.to_owned();
+            for (_, v) in f.iter_mut() {
+                if let Some(wildcard) = v.as_wildcard() {
+                    if let Some(wv) = ctx.store.get(wildcard) {
+                        *v = wv.to_owned();
+                    }
+                }
+            }
+            request_builder = request_builder.form(&f);
+        }
+        //multipart
+        if let Some(parts) = &self.config.multipart {
+            let mut form = reqwest::multipart::Form::new();
+            for (name, part) in parts {
+                match part.to_part(ctx).await {
+                    Ok(p) => form = form.part(name.to_string(), p),
+                    Err(e) => return Err(e)
+                };
+            }
+            request_builder = request_builder.multipart(form);
+        }
+        //body
+        let graphql_body = match self.config.graphql {
+            Some(ref g) => match g.body() {
+                Ok(b) => Some(b),
+                Err(e) => return Err(e)
+            },
+            None => None
+        };
+        if let Some(b) = graphql_body.as_ref().or(self.config.body.as_ref()) {
+            if let Some(ct) = b.content_type() {
+                if !self.config.has_header(reqwest::header::CONTENT_TYPE.as_str()) {
+                    request_builder = request_builder.header(reqwest::header::CONTENT_TYPE, ct);
+                }
+            }
+            if let Some(bb) = b.as_bytes(ctx) {
+                request_builder = request_builder.body(bb);
+            }
+        }
+        //sse
+        if self.config.sse.is_some() && !self.config.has_header(reqwest::header::ACCEPT.as_str()) {
+            request_builder = request_builder.header(reqwest::header::ACCEPT, "text/event-stream");
+        }
+        //auth
+        let auth = match &self.config.auth {
+            Some(a) => Some(a.to_owned()),
+            None if self.config.share_session => ctx.http_auth(self.config.session),
+            None => None
+        };
+        let retry_builder = request_builder.try_clone();
+        let mut rsp = match self.send(ctx, &client, request_builder, auth.as_ref(), false).await {
+            Ok(r) => r,
+            Err(e) => return Err(e)
+        };
+        //refresh credential and retry once
+        if rsp.status() == reqwest::StatusCode::UNAUTHORIZED {
+            if let (Some(a), Some(rb)) = (auth.as_ref().filter(|a| a.is_refreshable()), retry_builder) {
+                rsp = match self.send(ctx, &client, rb, Some(a), true).await {
+                    Ok(r) => r,
+                    Err(e) => return Err(e)
+                };
+            }
+        }
+        let status_code = &rsp.status().as_u16();
+        let task_id = self.task_id.to_owned();
+        let is_success = match self.config.expect_status {
+            Some(s) => *status_code == s,
+            None => rsp.status().is_success()
+        };
+        let mut report_item = ReportItem::failed(&task_id, format!("{} (status_code:{})", url, status_code));
+        if is_success {
+            let captured = match self.config.sse {
+                Some(ref sse) => {
+                    self.capture_head(ctx, &rsp);
+                    sse.read(ctx, rsp).await
+                }
+                None => match self.config.graphql {
+                    Some(ref g) => {
+                        self.capture_head(ctx, &rsp);
+                        g.read(ctx, rsp).await
+                    }
+                    None => self.capture(ctx, rsp).await.map(|_| None)
+                }
+            };
+            match captured {
+                Ok(Some(reason)) => {
+                    report_item = ReportItem::failed(&task_id, format!("{} ({})", url, reason));
+                },
+                Ok(None) => {
+                    if let Some(_expect) = &self.config.expect {
+                        report_item = super::check_expect(&task_id, url.as_str(), _expect, ctx);
+                    } else {
+                        report_item = ReportItem::success(&task_id, format!("{} succeed", url));
+                    }
+                },
+                Err(e) => return Err(e)
+            }
+        } 
+        ctx.report(report_item);
+        Ok(())
+    }
+
+    /// Apply authentication and signature, then send request.
+    async fn send(&self, ctx: &mut Context, client: &reqwest::Client, mut request_builder: RequestBuilder, auth: Option<&Auth>, refresh: bool) -> crate::Result<Response> {
+        if let Some(a) = auth {
+            request_builder = match a.apply(ctx, client, request_builder, refresh).await {
+                Ok(rb) => rb,
+                Err(e) => return Err(e)
+            };
+        }
+        let mut request = match request_builder.build() {
+            Ok(r) => r,
+            Err(e) => return Err(crate::error::request(e.into()))
+        };
+        if let Some(ref signer) = self.config.signer {
+            let canonical = CanonicalRequest::from_request(&request);
+            match signer.sign(&canonical, &ctx.store) {
+                Ok(signature) => signature.apply(&mut request)?,
+                Err(e) => return Err(e)
+            };
+        }
+        match client.execute(request).await {
+            Ok(r) => Ok(r),
+            Err(e) => Err(crate::error::request(e.into()))
+        }
+    }
+
+    /// Capture header and version values, which are available before reading body.
+    fn capture_head(&self, ctx: &mut Context, rsp: &Response) {
+        if let Some(header_caps) = self.config.filter_caps(|c| c.is_header()) {
+            for _cap in header_caps {
+                if let Capture::Header(ref _c) = _cap {
+                    if let Some(v) = rsp.headers().get(_c.key) {
+                        if let Ok(hv) = v.to_str() {
+                            ctx.store.set(_c.save_key.to_owned(), Value::Str(hv.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(version_caps) = self.config.filter_caps(|c| c.is_version()) {
+            let version = format!("{:?}", rsp.version());
+            for _cap in version_caps {
+                if let Capture::Version(ref _c) = _cap {
+                    ctx.store.set(_c.save_key.to_owned(), Value::Str(version.to_owned()));
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn capture(&self, ctx: &mut Context, rsp: Response) -> crate::Result<()> {
+        if self.config.capture.is_none() { 
+            return Ok(())
+        }
+        if let Some(ref _caps) = self.config.capture {
+            self.capture_head(ctx, &rsp);
+
+            let content_type = rsp.headers().get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string());
+            if let Ok(text) = rsp.text().await {
+                let deserializer = self.config.deserializer.resolve(content_type.as_deref(), &text);
+                if deserializer.is_json() {
+                    if let Some(ref json_caps) = self.config.filter_caps(|c| c.is_json()) {
+                        let json_values = match deserializer.json(&text) {
+                            Ok(v) => v,
+                            Err(e) => return Err(e) 
+                        };
+                        capture::save_json(json_caps, &json_values, &mut ctx.store);
+                    }
+                } else if deserializer.is_xml() || deserializer.is_html() {
+                    if let Some(ref xml_caps) = self.config.filter_caps(|c| c.is_xml()) {
+                        for _cap in xml_caps {
+                            if let Capture::Xml(_c) = _cap {
+                                if let Ok(cv) = xml::De::get(&text, _c.key) {
+                                    ctx.store.set(_c.save_key.to_owned(), cv);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+        }
+        Ok(())
+    }
+}
+
+/// HTTP task builder.
+/// # Example
+/// 
+/// ```
+/// use anole::task::http::{HttpTaskBuilder, Method};
+/// 
+/// let http_task = HttpTaskBuilder::new()
+///     .url("https://crates.io/crates/sqlx")
+///     .method(Method::Get)
+///     .build();
+/// ```
+pub struct HttpTaskBuilder<'a> {
+    pub(crate) url: &'a str,
+    pub(crate) method: Method,
+    pub(crate) deserializer: Deserializer,
+    pub(crate) header: Option<HashMap<&'a str, Value>>,
+    pub(crate) query: Option<HashMap<&'a str, Value>>,
+    pub(crate) form: Option<HashMap<&'a str, Value>>,
+    pub(crate) multipart: Option<Vec<(&'a str, Part)>>,
+    pub(crate) body: Option<Body>,
+    pub(crate) capture: Option<Vec<Capture<'a>>>,
+    pub(crate) verbose: bool,
+    pub(crate) session: &'a str,
+    pub(crate) share_session: bool,
+    pub(crate) auth: Option<Auth>,
+    pub(crate) signer: Option<Arc<dyn Signer>>,
+    pub(crate) tls: Option<TlsOption>,
+    pub(crate) proxy: Option<&'a str>,
+    pub(crate) redirect: Option<RedirectPolicy>,
+    pub(crate) resolve: Vec<(&'a str, &'a str)>,
+    pub(crate) http_version: Option<HttpVersion>,
+    pub(crate) expect_status: Option<u16>,
+    pub(crate) sse: Option<Sse<'a>>,
+    pub(crate) graphql: Option<GraphQL<'a>>,
+    pub(crate) expect: Option<(&'a str, Value)>,
+}
+
+impl<'a> HttpTaskBuilder<'a> {
+    /// Create an builder.
+    pub fn new() -> Self {
+        HttpTaskBuilder {
+            url: "",
+            method: Method::Get,
+            deserializer: Deserializer::Auto,
+            header: None,
+            query: None,
+            form: None,
+            multipart: None,
+            body: None,
+            capture: None,
+            verbose: false,
+            session: DEFAULT_SESSION,
+            share_session: true,
+            auth: None,
+            signer: None,
+            tls: None,
+            proxy: None,
+            redirect: None,
+            resolve: vec![],
+            http_version: None,
+            expect_status: None,
+            sse: None,
+            graphql: None,
+            expect: None,
+        }
+    }
+
+    /// Add http url.
+    pub fn url(mut self, url: &'a str) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// Add http method.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Specify deserializer for http response body, default is `Deserializer::Auto`.
+    pub fn deserializer(mut self, deserializer: Deserializer) -> Self {
+        self.deserializer = deserializer;
+        self
+    }
+
+    /// Add http header.
+    pub fn header(mut self, header: (&'a str, Value)) -> Self {
+        if self.header.is_none() {
+            self.header = Some(HashMap::new())
+        }
+        if let Some(ref mut h) = self.header {
+            h.insert(header.0, header.1);
+        }
+        self
+    }
+
+    /// Add http query params.
+    pub fn query(mut self, query: (&'a str, Value)) -> Self {
+        if self.query.is_none() {
+            self.query = Some(HashMap::new())
+        }
+        if let Some(ref mut q) = self.query {
+            q.insert(query.0, query.1);
+        }
+        self
+    }
+
+    /// Add form for http body.
+    pub fn form(mut self, form: (&'a str, Value)) -> Self {
+        if self.form.is_none() {
+            self.form = Some(HashMap::new())
+        }
+        if let Some(ref mut f) = self.form {
+            f.insert(form.0, form.1);
+        }
+        self
+    }
+
+    /// Add a part for multipart/form-data body.
+    /// # Example
+    /// 
+    /// ```
+    /// use anole::{task::http::{HttpTaskBuilder, Method}, value::{Part, Value}};
+    /// 
+    /// let http_task = HttpTaskBuilder::new()
+    ///     .url("https://example.com/v1/upload")
+    ///     .method(Method::Post)
+    ///     .multipart(("user_id", Part::text(":user_id")))
+    ///     .multipart(("avatar", Part::bytes(vec![0u8; 16]).file_name("avatar.png").mime("image/png")))
+    ///     .build();
+    /// ```
+    pub fn multipart(mut self, part: (&'a str, Part)) -> Self {
+        self.multipart.get_or_insert(vec![]).push(part);
+        self
+    }
+
+    /// Add http body.
+    pub fn body(mut self, body: Body) ->Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Add JSON body, `Content-Type: application/json` is set unless specified by header.
+    /// # Example
+    /// 
+    /// ```
+    /// use anole::{task::http::{HttpTaskBuilder, Method}, value::Value};
+    /// 
+    /// let http_task = HttpTaskBuilder::new()
+    ///     .url("https://example.com/v1/orders")
+    ///     .method(Method::Post)
+    ///     .json(Value::object([
+    ///         ("user_id", Value::from(":user_id")),
+    ///         ("items", Value::array([Value::object([("sku", ":sku")])])),
+    ///     ]))
+    ///     .build();
+    /// ```
+    pub fn json(mut self, v: Value) -> Self {
+        self.body = Some(Body::Json(v));
+        self
+    }
+
+    /// Add captures
+    pub fn capture(mut self, capture: Vec<Capture<'a>>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Whether enable debug info. In a session that is not verbose, the task uses a verbose client sharing the session cookies.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Send request with the named engine session, default is `DEFAULT_SESSION`.
+    pub fn session(mut self, name: &'a str) -> Self {
+        self.session = name;
+        self
+    }
+
+    /// Whether share engine session with other tasks, default is true.
+    /// A task not sharing session uses its own client without cookie store.
+    pub fn share_session(mut self, share: bool) -> Self {
+        self.share_session = share;
+        self
+    }
+
+    /// Specify authentication, overrides session authentication.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Specify TLS options, overrides session's.
+    /// A task overriding client options uses its own client instead of session's connections and cookies.
+    pub fn tls(mut self, tls: TlsOption) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Send request through proxy, overrides session's.
+    pub fn proxy(mut self, url: &'a str) -> Self {
+        self.proxy = Some(url);
+        self
+    }
+
+    /// Specify redirect following policy, overrides session's.
+    /// # Example
+    /// 
+    /// ```
+    /// use anole::{task::http::{HttpTaskBuilder, session::RedirectPolicy}, capture};
+    /// 
+    /// let http_task = HttpTaskBuilder::new()
+    ///     .url("https://example.com/login")
+    ///     .redirect(RedirectPolicy::None)
+    ///     .expect_status(302)
+    ///     .capture(vec![capture::header("location", "location")])
+    ///     .build();
+    /// ```
+    pub fn redirect(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect = Some(policy);
+        self
+    }
+
+    /// Pin domain to an IP address like curl `--resolve`, in addition to session's.
+    pub fn resolve(mut self, domain: &'a str, ip: &'a str) -> Self {
+        self.resolve.push((domain, ip));
+        self
+    }
+
+    /// Specify HTTP protocol version, overrides session's.
+    pub fn http_version(mut self, version: HttpVersion) -> Self {
+        self.http_version = Some(version);
+        self
+    }
+
+    /// Specify expected status code, default is any 2xx.
+    pub fn expect_status(mut self, status: u16) -> Self {
+        self.expect_status = Some(status);
+        self
+    }
+
+    /// Read response as Server-Sent Events stream instead of waiting for the whole body,
+    /// body captures are replaced by `Sse` captures.
+    pub fn sse(mut self, sse: Sse<'a>) -> Self {
+        self.sse = Some(sse);
+        self
+    }
+
+    /// Send GraphQL request as `POST` JSON body, body captures are replaced by `GraphQL` captures.
+    pub fn graphql(mut self, graphql: GraphQL<'a>) -> Self {
+        self.method = Method::Post;
+        self.graphql = Some(graphql);
+        self
+    }
+
+    /// Sign request before sending.
+    /// # Example
+    /// 
+    /// ```
+    /// use anole::task::http::{HttpTaskBuilder, sign::HmacSigner};
+    /// 
+    /// let http_task = HttpTaskBuilder::new()
+    ///     .url("https://example.com/v1/orders?page=1")
+    ///     .signer(HmacSigner::new(":secret").key_id("x-app-id", "anole"))
+    ///     .build();
+    /// ```
+    pub fn signer<S: Signer + 'static>(mut self, signer: S) -> Self {
+        self.signer = Some(Arc::new(signer));
+        self
+    }
+
+    /// Specify an expect condition for task.
+    pub fn expect(mut self, tup: (&'a str, Value)) -> Self {
+        self.expect = Some(tup);
+        self
+    }
+
+    pub(crate) fn client_override(&self) -> ClientOverride {
+        ClientOverride {
+            tls: self.tls.to_owned(),
+            proxy: self.proxy.map(|p| p.to_string()),
+            redirect: self.redirect,
+            resolve: self.resolve.iter().map(|(d, ip)| (d.to_string(), ip.to_string())).collect(),
+            http_version: self.http_version,
+            verbose: self.verbose,
+        }
+    }
+
+    pub(crate) fn has_header(&self, name: &str) -> bool {
+        if let Some(ref h) = self.header {
+            return h.keys().any(|k| k.eq_ignore_ascii_case(name));
+        }
+        false
+    }
+
+    pub(crate) fn filter_caps<T>(&self, f: T) -> Option<Vec<&Capture<'_>>> where T: FnMut(&&Capture) -> bool {
+        if let Some(ref caps) = self.capture {
+            let v = caps.iter().filter(f).collect::<Vec<&Capture>>();
+            return Some(v);
+        }
+        None
+    }
+
+    /// Build an HttpTask use this builder.
+    pub fn build(self) -> HttpTask<'a> {
+        HttpTask { config: self, task_id: crate::faker::uuid_v4() }
+    }
+
+}
+
+impl Default for HttpTaskBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
@@ -0,0 +1,256 @@
+use std::{net::{IpAddr, SocketAddr}, sync::Arc, time::Duration};
+
+use reqwest::cookie::Jar;
+
+use crate::tls::TlsOption;
+
+use super::auth::Auth;
+
+/// Name of the session used by HTTP tasks which do not specify one.
+pub const DEFAULT_SESSION: &str = "default";
+
+/// Redirect following policy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RedirectPolicy {
+    /// Do not follow redirects, the 3xx response is captured.
+    None,
+    /// Follow at most given redirects.
+    Limited(usize),
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        RedirectPolicy::Limited(10)
+    }
+}
+
+impl From<RedirectPolicy> for reqwest::redirect::Policy {
+    fn from(val: RedirectPolicy) -> Self {
+        match val {
+            RedirectPolicy::None => reqwest::redirect::Policy::none(),
+            RedirectPolicy::Limited(max) => reqwest::redirect::Policy::limited(max),
+        }
+    }
+}
+
+/// HTTP protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum HttpVersion {
+    /// HTTP/1.1, or HTTP/2 when negotiated by TLS ALPN.
+    #[default]
+    Auto,
+    /// Force HTTP/1.1.
+    Http1Only,
+    /// Use HTTP/2 without upgrade or negotiation (h2c or h2 with prior knowledge).
+    Http2PriorKnowledge,
+}
+
+/// HTTP session options, a session holds a pooled client and cookie store shared by HTTP tasks.
+/// # Example
+/// 
+/// ```
+/// use anole::{engine::Engine, task::http::session::HttpSessionOption};
+/// 
+/// let engine = Engine::new()
+///     .with_http_session("alice", HttpSessionOption::builder())
+///     .with_http_session("bob", HttpSessionOption::builder().cookie_store(false));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpSessionOption {
+    pub cookie_store: bool,
+    pub connect_timeout: Duration,
+    pub timeout: Option<Duration>,
+    pub verbose: bool,
+    pub auth: Option<Auth>,
+    pub tls: Option<TlsOption>,
+    pub proxy: Option<String>,
+    pub redirect: RedirectPolicy,
+    pub resolve: Vec<(String, String)>,
+    pub http_version: HttpVersion,
+}
+
+impl HttpSessionOption {
+
+    pub fn builder() -> Self {
+        HttpSessionOption {
+            cookie_store: true,
+            connect_timeout: Duration::from_secs(5),
+            timeout: None,
+            verbose: false,
+            auth: None,
+            tls: None,
+            proxy: None,
+            redirect: RedirectPolicy::default(),
+            resolve: vec![],
+            http_version: HttpVersion::default(),
+        }
+    }
+
+    /// Whether keep cookies between requests.
+    pub fn cookie_store(mut self, enable: bool) -> Self {
+        self.cookie_store = enable;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Total timeout of each request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Whether enable debug info
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Authentication for all tasks using this session, overridden by `HttpTaskBuilder::auth`.
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// TLS options of session client.
+    pub fn tls(mut self, tls: TlsOption) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Send all requests through proxy, e.g. `http://127.0.0.1:8888`.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Redirect following policy, default follows at most 10 redirects.
+    pub fn redirect(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect = policy;
+        self
+    }
+
+    /// Pin domain to an IP address like curl `--resolve`, port is taken from request url.
+    pub fn resolve(mut self, domain: &str, ip: &str) -> Self {
+        self.resolve.push((domain.to_string(), ip.to_string()));
+        self
+    }
+
+    /// Specify HTTP protocol version, default is `HttpVersion::Auto`.
+    pub fn http_version(mut self, version: HttpVersion) -> Self {
+        self.http_version = version;
+        self
+    }
+
+    /// Build client storing cookies in `jar` if given, or in a jar of its own.
+    pub(crate) fn build_client(&self, jar: Option<Arc<Jar>>) -> crate::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .connection_verbose(self.verbose)
+            .user_agent(format!("AnoleClient/{}", env!("CARGO_PKG_VERSION")));
+        builder = match jar {
+            Some(jar) if self.cookie_store => builder.cookie_provider(jar),
+            _ => builder.cookie_store(self.cookie_store),
+        };
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(ref tls) = self.tls {
+            builder = tls.apply_http(builder)?;
+        }
+        if let Some(ref proxy) = self.proxy {
+            match reqwest::Proxy::all(proxy) {
+                Ok(p) => builder = builder.proxy(p),
+                Err(e) => return Err(crate::error::create_client(e.into()))
+            }
+        }
+        builder = builder.redirect(self.redirect.into());
+        match self.http_version {
+            HttpVersion::Auto => (),
+            HttpVersion::Http1Only => builder = builder.http1_only(),
+            HttpVersion::Http2PriorKnowledge => builder = builder.http2_prior_knowledge(),
+        }
+        for (domain, ip) in &self.resolve {
+            match ip.parse::<IpAddr>() {
+                Ok(ip) => builder = builder.resolve(domain, SocketAddr::new(ip, 0)),
+                Err(e) => return Err(crate::error::create_client(e.into()))
+            }
+        }
+        match builder.build() {
+            Ok(c) => Ok(c),
+            Err(e) => Err(crate::error::create_client(e.into()))
+        }
+    }
+}
+
+impl Default for HttpSessionOption {
+    fn default() -> Self {
+        Self::builder()
+    }
+}
+
+/// Client options a task overrides on its session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ClientOverride {
+    pub(crate) tls: Option<TlsOption>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) redirect: Option<RedirectPolicy>,
+    pub(crate) resolve: Vec<(String, String)>,
+    pub(crate) http_version: Option<HttpVersion>,
+    pub(crate) verbose: bool,
+}
+
+impl ClientOverride {
+    pub(crate) fn apply(&self, mut option: HttpSessionOption) -> HttpSessionOption {
+        if let Some(ref tls) = self.tls {
+            option = option.tls(tls.to_owned());
+        }
+        if let Some(ref proxy) = self.proxy {
+            option = option.proxy(proxy);
+        }
+        if let Some(redirect) = self.redirect {
+            option = option.redirect(redirect);
+        }
+        for (domain, ip) in &self.resolve {
+            option = option.resolve(domain, ip);
+        }
+        if let Some(version) = self.http_version {
+            option = option.http_version(version);
+        }
+        if self.verbose {
+            option = option.verbose(true);
+        }
+        option
+    }
+}
+
+/// A named HTTP session, clients are created on first use.
+/// Tasks overriding client options get a client per override, all clients of the session share one cookie jar.
+pub(crate) struct HttpSession {
+    option: HttpSessionOption,
+    jar: Arc<Jar>,
+    clients: Vec<(ClientOverride, reqwest::Client)>,
+}
+
+impl HttpSession {
+    pub(crate) fn new(option: HttpSessionOption) -> Self {
+        HttpSession { option, jar: Arc::new(Jar::default()), clients: vec![] }
+    }
+
+    pub(crate) fn auth(&self) -> Option<Auth> {
+        self.option.auth.clone()
+    }
+
+    /// Returns client of the session with client options overridden.
+    pub(crate) fn client(&mut self, overrides: &ClientOverride) -> crate::Result<reqwest::Client> {
+        if let Some((_, c)) = self.clients.iter().find(|(o, _)| o == overrides) {
+            return Ok(c.clone());
+        }
+        let client = overrides.apply(self.option.clone()).build_client(Some(self.jar.clone()))?;
+        self.clients.push((overrides.clone(), client.clone()));
+        Ok(client)
+    }
+}
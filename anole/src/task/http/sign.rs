@@ -0,0 +1,308 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use sqlx::types::time::OffsetDateTime;
+
+use crate::{store::Store, value::Value};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Payload hash used when body is a stream and can not be hashed.
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Request signer, called after templating and authentication, before sending.
+/// # Example
+///
+/// ```
+/// use anole::{store::Store, task::http::sign::{CanonicalRequest, Signature, Signer}};
+///
+/// struct PathSigner;
+///
+/// impl Signer for PathSigner {
+///     fn sign(&self, req: &CanonicalRequest, _store: &Store) -> anole::Result<Signature> {
+///         Ok(Signature::default().header("x-sign", &format!("{}:{}", req.method, req.path)))
+///     }
+/// }
+/// ```
+pub trait Signer: Send + Sync {
+    fn sign(&self, req: &CanonicalRequest, store: &Store) -> crate::Result<Signature>;
+}
+
+/// Request parts to sign.
+#[derive(Debug, Clone)]
+pub struct CanonicalRequest {
+    pub method: String,
+    pub host: String,
+    pub path: String,
+    /// Query pairs sorted by key then value.
+    pub query: Vec<(String, String)>,
+    /// Lowercase header names with values, sorted by name.
+    pub headers: Vec<(String, String)>,
+    /// Hex encoded SHA-256 of body, `UNSIGNED_PAYLOAD` for stream body.
+    pub body_hash: String,
+    /// Unix timestamp in seconds.
+    pub timestamp: i64,
+    pub nonce: String,
+}
+
+impl CanonicalRequest {
+    pub(crate) fn from_request(req: &reqwest::Request) -> Self {
+        let url = req.url();
+        let mut query = url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+        query.sort();
+        let mut headers = req.headers().iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.as_str().to_string(), v.trim().to_string())))
+            .collect::<Vec<_>>();
+        headers.sort();
+        let body_hash = match req.body() {
+            Some(b) => match b.as_bytes() {
+                Some(bb) => sha256_hex(bb),
+                None => UNSIGNED_PAYLOAD.to_string(),
+            },
+            None => sha256_hex(b""),
+        };
+        let host = match url.port() {
+            Some(p) => format!("{}:{}", url.host_str().unwrap_or(""), p),
+            None => url.host_str().unwrap_or("").to_string(),
+        };
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+        CanonicalRequest {
+            method: req.method().as_str().to_string(),
+            host,
+            path: url.path().to_string(),
+            query,
+            headers,
+            body_hash,
+            timestamp,
+            nonce: crate::faker::uuid_v4().replace('-', ""),
+        }
+    }
+
+    /// Sorted query, RFC 3986 encoded, e.g. `a=1&b=2`.
+    pub fn canonical_query(&self) -> String {
+        self.query.iter()
+            .map(|(k, v)| format!("{}={}", uri_encode(k, true), uri_encode(v, true)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Returns header value by lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Headers and query params produced by signer.
+#[derive(Debug, Clone, Default)]
+pub struct Signature {
+    pub headers: Vec<(String, String)>,
+    pub query: Vec<(String, String)>,
+}
+
+impl Signature {
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn query(mut self, name: &str, value: &str) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub(crate) fn apply(self, req: &mut reqwest::Request) -> crate::Result<()> {
+        for (k, v) in self.headers {
+            let name = match reqwest::header::HeaderName::from_bytes(k.as_bytes()) {
+                Ok(n) => n,
+                Err(e) => return Err(crate::error::parse_value(e.into()))
+            };
+            let value = match reqwest::header::HeaderValue::from_str(&v) {
+                Ok(v) => v,
+                Err(e) => return Err(crate::error::parse_value(e.into()))
+            };
+            req.headers_mut().insert(name, value);
+        }
+        if !self.query.is_empty() {
+            let mut pairs = req.url_mut().query_pairs_mut();
+            for (k, v) in self.query {
+                pairs.append_pair(&k, &v);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// HMAC-SHA256 signer, signs string:
+///
+/// ```text
+/// METHOD\nPATH\nSORTED_QUERY\nBODY_SHA256\nTIMESTAMP\nNONCE
+/// ```
+///
+/// and sends timestamp, nonce and hex signature in headers.
+pub struct HmacSigner {
+    secret: Value,
+    key_id: Option<(String, Value)>,
+    signature_header: String,
+    timestamp_header: String,
+    nonce_header: String,
+    base64: bool,
+}
+
+impl HmacSigner {
+    /// Create signer with secret, secret supports wildcard.
+    pub fn new<S: Into<Value>>(secret: S) -> Self {
+        HmacSigner {
+            secret: secret.into(),
+            key_id: None,
+            signature_header: "x-signature".to_string(),
+            timestamp_header: "x-timestamp".to_string(),
+            nonce_header: "x-nonce".to_string(),
+            base64: false,
+        }
+    }
+
+    /// Send key id in header.
+    pub fn key_id<V: Into<Value>>(mut self, header: &str, key_id: V) -> Self {
+        self.key_id = Some((header.to_string(), key_id.into()));
+        self
+    }
+
+    pub fn signature_header(mut self, name: &str) -> Self {
+        self.signature_header = name.to_string();
+        self
+    }
+
+    pub fn timestamp_header(mut self, name: &str) -> Self {
+        self.timestamp_header = name.to_string();
+        self
+    }
+
+    pub fn nonce_header(mut self, name: &str) -> Self {
+        self.nonce_header = name.to_string();
+        self
+    }
+
+    /// Encode signature with base64 instead of hex.
+    pub fn base64(mut self, enable: bool) -> Self {
+        self.base64 = enable;
+        self
+    }
+
+    /// Returns the string to sign.
+    pub fn string_to_sign(req: &CanonicalRequest) -> String {
+        format!("{}\n{}\n{}\n{}\n{}\n{}", req.method, req.path, req.canonical_query(), req.body_hash, req.timestamp, req.nonce)
+    }
+}
+
+impl Signer for HmacSigner {
+    fn sign(&self, req: &CanonicalRequest, store: &Store) -> crate::Result<Signature> {
+        let secret = self.secret.resolve(store).as_str();
+        let mac = hmac_sha256(secret.as_bytes(), Self::string_to_sign(req).as_bytes());
+        let signature = if self.base64 { base64::encode(mac) } else { hex::encode(mac) };
+        let mut s = Signature::default()
+            .header(&self.timestamp_header, &req.timestamp.to_string())
+            .header(&self.nonce_header, &req.nonce)
+            .header(&self.signature_header, &signature);
+        if let Some((ref header, ref key_id)) = self.key_id {
+            s = s.header(header, &key_id.resolve(store).as_str());
+        }
+        Ok(s)
+    }
+}
+
+/// AWS Signature Version 4 style signer, sends `Authorization`, `x-amz-date`
+/// and optionally `x-amz-content-sha256` headers.
+pub struct SigV4Signer {
+    access_key: Value,
+    secret_key: Value,
+    session_token: Option<Value>,
+    region: String,
+    service: String,
+    content_sha256: bool,
+}
+
+impl SigV4Signer {
+    pub fn new<A: Into<Value>, S: Into<Value>>(access_key: A, secret_key: S, region: &str, service: &str) -> Self {
+        SigV4Signer {
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+            session_token: None,
+            region: region.to_string(),
+            service: service.to_string(),
+            content_sha256: false,
+        }
+    }
+
+    /// Send `x-amz-security-token` header.
+    pub fn session_token<V: Into<Value>>(mut self, token: V) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    /// Whether send and sign `x-amz-content-sha256` header (required by S3).
+    pub fn content_sha256(mut self, enable: bool) -> Self {
+        self.content_sha256 = enable;
+        self
+    }
+}
+
+impl Signer for SigV4Signer {
+    fn sign(&self, req: &CanonicalRequest, store: &Store) -> crate::Result<Signature> {
+        let amz_date = OffsetDateTime::from_unix_timestamp(req.timestamp).format("%Y%m%dT%H%M%SZ");
+        let date = &amz_date[..8];
+        let mut s = Signature::default().header("x-amz-date", &amz_date);
+        if self.content_sha256 {
+            s = s.header("x-amz-content-sha256", &req.body_hash);
+        }
+        if let Some(ref token) = self.session_token {
+            s = s.header("x-amz-security-token", &token.resolve(store).as_str());
+        }
+
+        let mut headers: Vec<(String, String)> = vec![("host".to_string(), req.host.to_owned())];
+        headers.extend(req.headers.iter().filter(|(k, _)| k.starts_with("x-amz-")).cloned());
+        headers.extend(s.headers.iter().cloned());
+        headers.sort();
+        headers.dedup_by(|a, b| a.0 == b.0);
+        let canonical_headers = headers.iter().map(|(k, v)| format!("{}:{}\n", k, v)).collect::<String>();
+        let signed_headers = headers.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>().join(";");
+        let path = if req.path.is_empty() { "/".to_string() } else { uri_encode(&req.path, false) };
+        let canonical_request = format!("{}\n{}\n{}\n{}\n{}\n{}", req.method, path, req.canonical_query(), canonical_headers, signed_headers, req.body_hash);
+
+        let scope = format!("{}/{}/{}/aws4_request", date, self.region, self.service);
+        let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, sha256_hex(canonical_request.as_bytes()));
+        let secret = format!("AWS4{}", self.secret_key.resolve(store).as_str());
+        let k_date = hmac_sha256(secret.as_bytes(), date.as_bytes());
+        let k_region = hmac_sha256(&k_date, self.region.as_bytes());
+        let k_service = hmac_sha256(&k_region, self.service.as_bytes());
+        let k_signing = hmac_sha256(&k_service, b"aws4_request");
+        let signature = hex::encode(hmac_sha256(&k_signing, string_to_sign.as_bytes()));
+
+        let authorization = format!("AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key.resolve(store).as_str(), scope, signed_headers, signature);
+        Ok(s.header("authorization", &authorization))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// RFC 3986 encoding, keeps `/` when `encode_slash` is false.
+fn uri_encode(s: &str, encode_slash: bool) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(b as char),
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
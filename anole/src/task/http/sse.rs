@@ -0,0 +1,242 @@
+use std::{sync::Arc, time::Duration};
+
+use log::debug;
+use reqwest::Response;
+
+use crate::{capture::{self, Capture}, context::Context, value::{self, Value}};
+
+/// A Server-Sent Event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Event type, `message` when not specified.
+    pub event: String,
+    pub data: String,
+    pub id: Option<String>,
+}
+
+enum Until {
+    Event(String),
+    JsonEq(String, Value),
+    Custom(Arc<dyn Fn(&Event) -> bool + Send + Sync>),
+}
+
+/// Server-Sent Events streaming options, response body is read event by event
+/// until condition, event count or timeout is reached.
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use anole::{task::http::{HttpTaskBuilder, sse::Sse}, capture, value::Value};
+///
+/// let http_task = HttpTaskBuilder::new()
+///     .url("https://example.com/v1/jobs/:job_id/progress")
+///     .sse(Sse::new()
+///         .until_event("done")
+///         .timeout(Duration::from_secs(60))
+///         .capture_event("progress", vec![capture::json("percent", "percent")])
+///         .expect_order(vec!["started", "progress", "done"]))
+///     .expect(("percent", Value::I32(100)))
+///     .build();
+/// ```
+pub struct Sse<'a> {
+    until: Option<Until>,
+    max_events: Option<usize>,
+    timeout: Duration,
+    capture: Vec<(Option<&'a str>, Vec<Capture<'a>>)>,
+    count_key: Option<&'a str>,
+    order: Vec<&'a str>,
+}
+
+impl<'a> Sse<'a> {
+    pub fn new() -> Self {
+        Sse {
+            until: None,
+            max_events: None,
+            timeout: Duration::from_secs(30),
+            capture: vec![],
+            count_key: None,
+            order: vec![],
+        }
+    }
+
+    /// Stop after receiving event of given type, task fails if not received.
+    pub fn until_event(mut self, event: &str) -> Self {
+        self.until = Some(Until::Event(event.to_string()));
+        self
+    }
+
+    /// Stop after receiving event whose JSON data at path equals value (wildcard supported),
+    /// task fails if not received.
+    pub fn until_json_eq<V: Into<Value>>(mut self, path: &str, v: V) -> Self {
+        self.until = Some(Until::JsonEq(path.to_string(), v.into()));
+        self
+    }
+
+    /// Stop after receiving event matching predicate, task fails if not received.
+    pub fn until<F>(mut self, f: F) -> Self where F: Fn(&Event) -> bool + Send + Sync + 'static {
+        self.until = Some(Until::Custom(Arc::new(f)));
+        self
+    }
+
+    /// Stop after given events, task fails if fewer are received.
+    pub fn max_events(mut self, count: usize) -> Self {
+        self.max_events = Some(count);
+        self
+    }
+
+    /// Maximum time to read stream, default is 30 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Capture from JSON data of every event, later events overwrite earlier values.
+    pub fn capture(mut self, caps: Vec<Capture<'a>>) -> Self {
+        self.capture.push((None, caps));
+        self
+    }
+
+    /// Capture from JSON data of events of given type.
+    pub fn capture_event(mut self, event: &'a str, caps: Vec<Capture<'a>>) -> Self {
+        self.capture.push((Some(event), caps));
+        self
+    }
+
+    /// Save received event count into store.
+    pub fn count(mut self, save_key: &'a str) -> Self {
+        self.count_key = Some(save_key);
+        self
+    }
+
+    /// Expect event types appear in this order, other events may be interleaved.
+    pub fn expect_order(mut self, events: Vec<&'a str>) -> Self {
+        self.order = events;
+        self
+    }
+
+    fn is_until(&self, e: &Event, json: Option<&serde_json::Value>, ctx: &Context) -> bool {
+        match &self.until {
+            None => false,
+            Some(Until::Event(name)) => e.event == *name,
+            Some(Until::JsonEq(path, v)) => match json.and_then(|j| value::parse_json_value(j, path.to_owned())) {
+                Some(jv) => Value::from(&jv) == v.resolve(&ctx.store),
+                None => false,
+            },
+            Some(Until::Custom(f)) => f(e),
+        }
+    }
+
+    /// Read events from response, returns failure description when conditions are not met.
+    pub(crate) async fn read(&self, ctx: &mut Context, mut rsp: Response) -> crate::Result<Option<String>> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let mut parser = Parser::default();
+        let mut events: Vec<String> = vec![];
+        let mut reached = false;
+        'read: loop {
+            let chunk = match tokio::time::timeout_at(deadline, rsp.chunk()).await {
+                Ok(Ok(Some(c))) => c,
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => return Err(crate::error::request(e.into())),
+                Err(_) => {
+                    debug!("sse timeout after {} events", events.len());
+                    break;
+                }
+            };
+            for e in parser.feed(&chunk) {
+                let json = serde_json::from_str::<serde_json::Value>(&e.data).ok();
+                if let Some(ref j) = json {
+                    for (event, caps) in &self.capture {
+                        if event.map(|n| n == e.event).unwrap_or(true) {
+                            capture::save_json(&caps.iter().collect::<Vec<_>>(), j, &mut ctx.store);
+                        }
+                    }
+                }
+                events.push(e.event.to_owned());
+                if self.is_until(&e, json.as_ref(), ctx) {
+                    reached = true;
+                    break 'read;
+                }
+                if self.max_events.map(|m| events.len() >= m).unwrap_or(false) {
+                    break 'read;
+                }
+            }
+        }
+
+        if let Some(count_key) = self.count_key {
+            ctx.store.set(count_key.to_string(), Value::U64(events.len() as u64));
+        }
+        if self.until.is_some() && !reached {
+            return Ok(Some(format!("until condition not reached after {} events", events.len())));
+        }
+        if let Some(m) = self.max_events {
+            if events.len() < m {
+                return Ok(Some(format!("expect {} events but {}", m, events.len())));
+            }
+        }
+        if !self.order.is_empty() {
+            let mut expected = self.order.iter().peekable();
+            for e in &events {
+                if expected.peek().map(|n| **n == e.as_str()).unwrap_or(false) {
+                    expected.next();
+                }
+            }
+            if let Some(missing) = expected.next() {
+                return Ok(Some(format!("expect event order {:?} but {:?}, missing '{}'", self.order, events, missing)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Default for Sse<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incremental `text/event-stream` parser.
+#[derive(Default)]
+pub(crate) struct Parser {
+    buf: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl Parser {
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        self.buf.extend_from_slice(chunk);
+        let mut events = vec![];
+        while let Some(pos) = self.buf.iter().position(|b| *b == b'\n') {
+            let line = self.buf.drain(..=pos).collect::<Vec<u8>>();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\n').trim_end_matches('\r');
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(Event {
+                        event: self.event.take().unwrap_or_else(|| "message".to_string()),
+                        data: self.data.join("\n"),
+                        id: self.id.to_owned(),
+                    });
+                }
+                self.data.clear();
+                self.event = None;
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, v) = match line.find(':') {
+                Some(idx) => (&line[..idx], line[idx + 1..].strip_prefix(' ').unwrap_or(&line[idx + 1..])),
+                None => (line, ""),
+            };
+            match field {
+                "event" => self.event = Some(v.to_string()),
+                "data" => self.data.push(v.to_string()),
+                "id" => self.id = Some(v.to_string()),
+                _ => (),
+            }
+        }
+        events
+    }
+}
@@ -1,22 +1,37 @@
-use crate::context::Context;
+use crate::{context::Context, store::Store, value::Value, report::ReportItem};
 
-use self::{http::HttpTask, db::mysql::MysqlTask};
+use self::{http::HttpTask, db::{DatabaseTask, mysql::MysqlTask, postgres::PostgresTask, sqlite::SqliteTask}, websocket::WebSocketTask, grpc::GrpcTask, redis::RedisTask};
 
 
 pub mod chain;
 pub mod http;
 pub mod db;
+pub mod websocket;
+pub mod grpc;
+pub mod redis;
 
 pub enum Task<'a> {
     Http(Box<HttpTask<'a>>),
+    Database(DatabaseTask<'a>),
     Mysql(MysqlTask<'a>),
+    Postgres(PostgresTask<'a>),
+    Sqlite(SqliteTask<'a>),
+    WebSocket(Box<WebSocketTask<'a>>),
+    Grpc(Box<GrpcTask<'a>>),
+    Redis(RedisTask<'a>),
 }
 
 impl<'a> Task<'a> {
     pub async fn execute(&mut self, ctx: &mut Context) -> crate::Result<()> {
         match self {
             Self::Http(t) => t.execute(ctx).await,
-            Self::Mysql(t) => t.execute(ctx).await
+            Self::Database(t) => t.execute(ctx).await,
+            Self::Mysql(t) => t.execute(ctx).await,
+            Self::Postgres(t) => t.execute(ctx).await,
+            Self::Sqlite(t) => t.execute(ctx).await,
+            Self::WebSocket(t) => t.execute(ctx).await,
+            Self::Grpc(t) => t.execute(ctx).await,
+            Self::Redis(t) => t.execute(ctx).await,
         }
     }
 }
@@ -27,9 +42,80 @@ impl<'a> From<HttpTask<'a>> for Task<'a> {
     }
 }
 
+impl<'a> From<DatabaseTask<'a>> for Task<'a> {
+    fn from(t: DatabaseTask<'a>) -> Self {
+        Task::Database(t)
+    }
+}
+
 impl<'a> From<MysqlTask<'a>> for Task<'a> {
     fn from(t: MysqlTask<'a>) -> Self {
         Task::Mysql(t)
     }
 }
 
+impl<'a> From<PostgresTask<'a>> for Task<'a> {
+    fn from(t: PostgresTask<'a>) -> Self {
+        Task::Postgres(t)
+    }
+}
+
+impl<'a> From<SqliteTask<'a>> for Task<'a> {
+    fn from(t: SqliteTask<'a>) -> Self {
+        Task::Sqlite(t)
+    }
+}
+
+impl<'a> From<WebSocketTask<'a>> for Task<'a> {
+    fn from(t: WebSocketTask<'a>) -> Self {
+        Task::WebSocket(Box::new(t))
+    }
+}
+
+impl<'a> From<GrpcTask<'a>> for Task<'a> {
+    fn from(t: GrpcTask<'a>) -> Self {
+        Task::Grpc(Box::new(t))
+    }
+}
+
+impl<'a> From<RedisTask<'a>> for Task<'a> {
+    fn from(t: RedisTask<'a>) -> Self {
+        Task::Redis(t)
+    }
+}
+
+/// Replace `:key` path segments of url with store values.
+/// Returns the segment whose value is not found.
+pub(crate) fn replace_path(url: &mut url::Url, store: &Store) -> Result<(), String> {
+    if let Some(mut path_segments) = url.path_segments() {
+        let mut paths: Vec<String> = vec![];
+        for p in path_segments.by_ref() {
+            if let Some(k) = p.strip_prefix(':') {
+                if let Some(v) = store.get(k.to_string()) {
+                    paths.push(v.as_str());
+                } else {
+                    return Err(p.to_string());
+                }
+            } else {
+                paths.push(p.to_string());
+            }
+        }
+        if let Some(path) = paths.into_iter().reduce(|mut p, x| {
+            p.push('/');
+            p.push_str(&x);
+            p
+        }) {
+            url.set_path(&path);
+        }
+    }
+    Ok(())
+}
+
+/// Check expect condition against store value.
+pub(crate) fn check_expect(task_id: &str, target: &str, expect: &(&str, Value), ctx: &Context) -> ReportItem {
+    match ctx.store.get(expect.0.to_string()) {
+        Some(v) if v.loose_eq(&expect.1) => ReportItem::success(task_id, format!("{} expect pass", expect.0)),
+        Some(v) => ReportItem::failed(task_id, format!("{} ({} expect {:?} but {:?})", target, expect.0, expect.1, v)),
+        None => ReportItem::failed(task_id, format!("{} ({} expect {:?} but not found)", target, expect.0, expect.1)),
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+
+use log::debug;
+use ::redis::{aio::Connection, Client, Value as Reply};
+
+use crate::{capture::{self, Capture}, context::Context, report::ReportItem, store::Store, value::Value};
+
+/// A Redis task running commands in order on one connection.
+///
+/// ```no_run
+/// use anole::{task::redis::{RedisTask, RedisCommand}, capture, value::Value};
+///
+/// let redis_task = RedisTask::default()
+///     .url("redis://127.0.0.1:6379/0")
+///     .with_command(RedisCommand::exists("session::user_id").save("cached").expect(("cached", Value::I64(1))))
+///     .with_command(RedisCommand::get("session::user_id").capture(vec![capture::json("name", "user_name")]));
+/// ```
+#[derive(Default)]
+pub struct RedisTask<'a> {
+    url: Option<&'a str>,
+    commands: Vec<RedisCommand<'a>>,
+}
+
+impl<'a> RedisTask<'a> {
+
+    /// Specify Redis server url, e.g. `redis://:password@127.0.0.1:6379/0`.
+    pub fn url(mut self, url: &'a str) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    /// Add an Redis command.
+    pub fn with_command(mut self, c: RedisCommand<'a>) -> Self {
+        self.commands.push(c);
+        self
+    }
+
+    /// Execute all Redis commands.
+    pub async fn execute(&self, ctx: &mut Context) -> crate::Result<()> {
+        let url = match self.url {
+            Some(u) => u,
+            None => return Err(crate::error::create_client("Redis url Empty".into()))
+        };
+        let client = match Client::open(url) {
+            Ok(c) => c,
+            Err(e) => return Err(crate::error::create_client(e.into()))
+        };
+        let mut con = match client.get_async_connection().await {
+            Ok(c) => c,
+            Err(e) => return Err(crate::error::create_client(e.into()))
+        };
+        for c in &self.commands {
+            match c.execute(&mut con, ctx).await {
+                Ok(item) => ctx.report(item),
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Redis command, keys and arguments are templates whose `:name` placeholders are replaced with store values.
+#[derive(Debug)]
+pub struct RedisCommand<'a> {
+    name: &'a str,
+    args: Vec<String>,
+    save_key: Option<&'a str>,
+    capture: Option<Vec<Capture<'a>>>,
+    expect: Option<(&'a str, Value)>,
+    task_id: String,
+}
+
+impl<'a> RedisCommand<'a> {
+
+    /// Raw command, e.g. `RedisCommand::cmd("HSET").arg("user::user_id").arg("name").arg(":user_name")`.
+    pub fn cmd(name: &'a str) -> Self {
+        RedisCommand { name, args: vec![], save_key: None, capture: None, expect: None, task_id: crate::faker::uuid_v4() }
+    }
+
+    /// `GET key`, replies the string value or `Value::Null` if key is missing.
+    pub fn get(key: &str) -> Self {
+        Self::cmd("GET").arg(key)
+    }
+
+    /// `SET key value`.
+    pub fn set(key: &str, value: &str) -> Self {
+        Self::cmd("SET").arg(key).arg(value)
+    }
+
+    /// `DEL key`, replies the number of keys removed.
+    pub fn del(key: &str) -> Self {
+        Self::cmd("DEL").arg(key)
+    }
+
+    /// `HGETALL key`, replies an object of fields and values.
+    pub fn hgetall(key: &str) -> Self {
+        Self::cmd("HGETALL").arg(key)
+    }
+
+    /// `TTL key`, replies remaining seconds, `-1` without expiry and `-2` if key is missing.
+    pub fn ttl(key: &str) -> Self {
+        Self::cmd("TTL").arg(key)
+    }
+
+    /// `EXISTS key`, replies `1` if key exists, `0` otherwise.
+    pub fn exists(key: &str) -> Self {
+        Self::cmd("EXISTS").arg(key)
+    }
+
+    /// `LRANGE key start stop`, replies an array of elements.
+    pub fn lrange(key: &str, start: isize, stop: isize) -> Self {
+        Self::cmd("LRANGE").arg(key).arg(start).arg(stop)
+    }
+
+    /// Append an argument.
+    pub fn arg<T: ToString>(mut self, arg: T) -> Self {
+        self.args.push(arg.to_string());
+        self
+    }
+
+    /// Save reply into store.
+    /// Integers are saved as `Value::I64`, nil as `Value::Null`, binary strings as `Value::Bytes`.
+    pub fn save(mut self, save_key: &'a str) -> Self {
+        self.save_key = Some(save_key);
+        self
+    }
+
+    /// Add JSON captures, string replies are parsed as JSON, other replies are read as their JSON form.
+    pub fn capture(mut self, capture: Vec<Capture<'a>>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    /// Specify an expect condition for command.
+    pub fn expect(mut self, tup: (&'a str, Value)) -> Self {
+        self.expect = Some(tup);
+        self
+    }
+
+    async fn execute(&self, con: &mut Connection, ctx: &mut Context) -> crate::Result<ReportItem> {
+        let args = self.args.iter().map(|a| render(a, &ctx.store)).collect::<Vec<_>>();
+        let target = format!("{} {}", self.name, args.join(" "));
+        let mut cmd = ::redis::cmd(self.name);
+        for a in &args {
+            cmd.arg(a);
+        }
+        let reply = match cmd.query_async::<_, Reply>(con).await {
+            Ok(r) => r,
+            Err(e) if e.is_io_error() => return Err(crate::error::request(e.into())),
+            Err(e) => return Ok(ReportItem::failed(&self.task_id, format!("{} ({})", target, e)))
+        };
+        debug!("redis {} reply: {:?}", target, reply);
+        let value = if self.name.eq_ignore_ascii_case("HGETALL") {
+            pairs(reply)
+        } else {
+            to_value(reply)
+        };
+        if let Some(ref caps) = self.capture {
+            let json = match &value {
+                Value::Str(s) => serde_json::from_str(s).unwrap_or(serde_json::Value::Null),
+                v => serde_json::to_value(v).unwrap_or(serde_json::Value::Null),
+            };
+            capture::save_json(&caps.iter().filter(|c| c.is_json()).collect::<Vec<_>>(), &json, &mut ctx.store);
+        }
+        if let Some(save_key) = self.save_key {
+            ctx.store.set(save_key.to_string(), value);
+        }
+        Ok(match &self.expect {
+            Some(_expect) => super::check_expect(&self.task_id, &target, _expect, ctx),
+            None => ReportItem::success(&self.task_id, format!("{} succeed", target)),
+        })
+    }
+}
+
+/// Replace `:name` placeholders at the start of argument or following a `:` delimiter with store values,
+/// placeholders not found in store are kept. So `session::user_id` is `session:42` while `user:profile` stays as is.
+fn render(arg: &str, store: &Store) -> String {
+    let mut out = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(idx) = rest.find(':') {
+        out.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+        if out.is_empty() || out.ends_with(':') {
+            let end = after.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(after.len());
+            if let Some(v) = store.get(after[..end].to_string()).filter(|_| end > 0) {
+                out.push_str(&v.as_str());
+                rest = &after[end..];
+                continue;
+            }
+        }
+        out.push(':');
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+fn to_value(reply: Reply) -> Value {
+    match reply {
+        Reply::Nil => Value::Null,
+        Reply::Int(i) => Value::I64(i),
+        Reply::Data(d) => match String::from_utf8(d) {
+            Ok(s) => Value::Str(s),
+            Err(e) => Value::Bytes(e.into_bytes())
+        },
+        Reply::Bulk(items) => Value::Array(items.into_iter().map(to_value).collect()),
+        Reply::Status(s) => Value::Str(s),
+        Reply::Okay => Value::Str("OK".to_string()),
+    }
+}
+
+/// Fold flat field and value reply into an object.
+fn pairs(reply: Reply) -> Value {
+    match to_value(reply) {
+        Value::Array(items) => {
+            let mut m = BTreeMap::new();
+            let mut it = items.into_iter();
+            while let (Some(k), Some(v)) = (it.next(), it.next()) {
+                m.insert(k.as_str(), v);
+            }
+            Value::Object(m)
+        },
+        v => v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> Store {
+        let mut store = Store::new();
+        store.set("user_id".to_string(), Value::I32(42));
+        store.set("profile".to_string(), Value::from("admin"));
+        store
+    }
+
+    #[test]
+    fn test_render_start_of_arg() {
+        let store = store();
+        assert_eq!(render(":user_id", &store), "42");
+        assert_eq!(render(":user_id:profile", &store), "42:profile");
+        assert_eq!(render(":user_id-tail", &store), "42-tail");
+    }
+
+    #[test]
+    fn test_render_double_colon() {
+        let store = store();
+        assert_eq!(render("session::user_id", &store), "session:42");
+        assert_eq!(render("a::user_id::profile", &store), "a:42:admin");
+        assert_eq!(render("::user_id", &store), ":42");
+        // only placeholders following a delimiter are replaced
+        assert_eq!(render("user:profile", &store), "user:profile");
+    }
+
+    #[test]
+    fn test_render_unknown_key() {
+        let store = store();
+        assert_eq!(render(":missing", &store), ":missing");
+        assert_eq!(render("session::missing", &store), "session::missing");
+        assert_eq!(render("session:", &store), "session:");
+        assert_eq!(render(":", &store), ":");
+        assert_eq!(render("plain", &store), "plain");
+    }
+}
@@ -0,0 +1,298 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use log::debug;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, http::{HeaderName, HeaderValue}};
+
+use crate::{capture::{self, Capture}, context::Context, report::ReportItem, value::{self, Value}};
+
+/// WebSocket message to send.
+pub enum Message {
+    /// Text frame, wildcard is replaced with store value.
+    Text(Value),
+    /// Binary frame.
+    Binary(bytes::Bytes),
+    /// JSON text frame, wildcards in the tree are replaced with store values.
+    Json(Value),
+}
+
+/// Received frame passed to `Wait::matching` predicate.
+#[derive(Debug)]
+pub enum Frame<'f> {
+    Text(&'f str),
+    Binary(&'f [u8]),
+}
+
+enum Matcher {
+    Any,
+    TextContains(Value),
+    JsonEq(String, Value),
+    Custom(Arc<dyn Fn(&Frame) -> bool + Send + Sync>),
+}
+
+/// Wait for a received message matching predicate, then capture from it.
+pub struct Wait<'a> {
+    matcher: Matcher,
+    timeout: Duration,
+    capture: Option<Vec<Capture<'a>>>,
+}
+
+impl<'a> Wait<'a> {
+    fn new(matcher: Matcher) -> Self {
+        Wait { matcher, timeout: Duration::from_secs(5), capture: None }
+    }
+
+    /// Match next data frame.
+    pub fn any() -> Self {
+        Self::new(Matcher::Any)
+    }
+
+    /// Match text frame containing given text, wildcard supported.
+    pub fn text_contains<V: Into<Value>>(text: V) -> Self {
+        Self::new(Matcher::TextContains(text.into()))
+    }
+
+    /// Match JSON frame whose value at capture syntax path equals given value, wildcard supported.
+    pub fn json_eq<V: Into<Value>>(path: &str, v: V) -> Self {
+        Self::new(Matcher::JsonEq(path.to_string(), v.into()))
+    }
+
+    /// Match frame with custom predicate.
+    pub fn matching<F>(f: F) -> Self where F: Fn(&Frame) -> bool + Send + Sync + 'static {
+        Self::new(Matcher::Custom(Arc::new(f)))
+    }
+
+    /// Maximum time to wait, default is 5 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Capture values from the matched JSON frame.
+    pub fn capture(mut self, capture: Vec<Capture<'a>>) -> Self {
+        self.capture = Some(capture);
+        self
+    }
+
+    fn is_match(&self, frame: &Frame, json: Option<&serde_json::Value>, ctx: &Context) -> bool {
+        match &self.matcher {
+            Matcher::Any => true,
+            Matcher::TextContains(t) => match frame {
+                Frame::Text(s) => s.contains(&t.resolve(&ctx.store).as_str()),
+                Frame::Binary(_) => false,
+            },
+            Matcher::JsonEq(path, v) => match json.and_then(|j| value::parse_json_value(j, path.to_owned())) {
+                Some(jv) => Value::from(&jv) == v.resolve(&ctx.store),
+                None => false,
+            },
+            Matcher::Custom(f) => f(frame),
+        }
+    }
+}
+
+enum Step<'a> {
+    Send(Message),
+    Wait(Wait<'a>),
+}
+
+/// A WebSocket task.
+pub struct WebSocketTask<'a> {
+    pub(crate) config: WebSocketTaskBuilder<'a>,
+    pub(crate) task_id: String,
+}
+
+impl WebSocketTask<'_> {
+    pub async fn execute(&mut self, ctx: &mut Context) -> crate::Result<()> {
+        let mut url = match url::Url::parse(self.config.url) {
+            Ok(u) => u,
+            Err(e) => return Err(crate::error::parse_value(e.into()))
+        };
+        if let Err(p) = super::replace_path(&mut url, &ctx.store) {
+            ctx.report(ReportItem::failed(&self.task_id, format!("{} (not found '{}' value)", url, p)));
+            return Ok(());
+        }
+        let mut request = match url.as_str().into_client_request() {
+            Ok(r) => r,
+            Err(e) => return Err(crate::error::parse_value(e.into()))
+        };
+        if let Some(ref h) = self.config.header {
+            for (k, v) in h {
+                let name = match HeaderName::from_bytes(k.as_bytes()) {
+                    Ok(n) => n,
+                    Err(e) => return Err(crate::error::parse_value(e.into()))
+                };
+                let value = match HeaderValue::from_str(&v.resolve(&ctx.store).as_str()) {
+                    Ok(v) => v,
+                    Err(e) => return Err(crate::error::parse_value(e.into()))
+                };
+                request.headers_mut().insert(name, value);
+            }
+        }
+
+        let connect = tokio_tungstenite::connect_async(request);
+        let (mut stream, _) = match tokio::time::timeout(self.config.connect_timeout, connect).await {
+            Ok(Ok(s)) => s,
+            Ok(Err(e)) => return Err(crate::error::request(e.into())),
+            Err(e) => return Err(crate::error::request(e.into()))
+        };
+
+        for (idx, step) in self.config.steps.iter().enumerate() {
+            match step {
+                Step::Send(m) => {
+                    let msg = match m {
+                        Message::Text(v) => tungstenite::Message::Text(v.resolve(&ctx.store).as_str()),
+                        Message::Binary(b) => tungstenite::Message::Binary(b.to_vec()),
+                        Message::Json(v) => match serde_json::to_string(&v.resolve(&ctx.store)) {
+                            Ok(s) => tungstenite::Message::Text(s),
+                            Err(e) => return Err(crate::error::parse_value(e.into()))
+                        },
+                    };
+                    if let Err(e) = stream.send(msg).await {
+                        return Err(crate::error::request(e.into()));
+                    }
+                }
+                Step::Wait(w) => {
+                    let deadline = tokio::time::Instant::now() + w.timeout;
+                    loop {
+                        let msg = match tokio::time::timeout_at(deadline, stream.next()).await {
+                            Ok(Some(Ok(m))) => m,
+                            Ok(Some(Err(e))) => return Err(crate::error::request(e.into())),
+                            Ok(None) => {
+                                ctx.report(ReportItem::failed(&self.task_id, format!("{} (connection closed at step {})", url, idx)));
+                                return Ok(());
+                            }
+                            Err(_) => {
+                                ctx.report(ReportItem::failed(&self.task_id, format!("{} (wait timeout at step {})", url, idx)));
+                                let _ = stream.close(None).await;
+                                return Ok(());
+                            }
+                        };
+                        let frame = match msg {
+                            tungstenite::Message::Text(ref t) => Frame::Text(t),
+                            tungstenite::Message::Binary(ref b) => Frame::Binary(b),
+                            _ => continue,
+                        };
+                        let json = match frame {
+                            Frame::Text(t) => serde_json::from_str::<serde_json::Value>(t).ok(),
+                            Frame::Binary(_) => None,
+                        };
+                        if !w.is_match(&frame, json.as_ref(), ctx) {
+                            debug!("skip frame {:?}", frame);
+                            continue;
+                        }
+                        if let (Some(caps), Some(j)) = (&w.capture, &json) {
+                            capture::save_json(&caps.iter().collect::<Vec<_>>(), j, &mut ctx.store);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        let _ = stream.close(None).await;
+
+        let report_item = match &self.config.expect {
+            Some(_expect) => super::check_expect(&self.task_id, url.as_str(), _expect, ctx),
+            None => ReportItem::success(&self.task_id, format!("{} succeed", url)),
+        };
+        ctx.report(report_item);
+        Ok(())
+    }
+}
+
+/// WebSocket task builder.
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use anole::{task::websocket::{WebSocketTaskBuilder, Wait}, capture, value::Value};
+///
+/// let ws_task = WebSocketTaskBuilder::new()
+///     .url("wss://example.com/notify/:user_id")
+///     .header(("Authorization", Value::from(":token")))
+///     .send_json(Value::object([("action", "subscribe")]))
+///     .wait(Wait::json_eq("type", "notification")
+///         .timeout(Duration::from_secs(10))
+///         .capture(vec![capture::json("data.id", "notification_id")]))
+///     .build();
+/// ```
+pub struct WebSocketTaskBuilder<'a> {
+    pub(crate) url: &'a str,
+    pub(crate) header: Option<HashMap<&'a str, Value>>,
+    pub(crate) connect_timeout: Duration,
+    steps: Vec<Step<'a>>,
+    pub(crate) expect: Option<(&'a str, Value)>,
+}
+
+impl<'a> WebSocketTaskBuilder<'a> {
+    /// Create an builder.
+    pub fn new() -> Self {
+        WebSocketTaskBuilder {
+            url: "",
+            header: None,
+            connect_timeout: Duration::from_secs(5),
+            steps: vec![],
+            expect: None,
+        }
+    }
+
+    /// Add websocket url, `:key` path segments are replaced with store values.
+    pub fn url(mut self, url: &'a str) -> Self {
+        self.url = url;
+        self
+    }
+
+    /// Add handshake header.
+    pub fn header(mut self, header: (&'a str, Value)) -> Self {
+        self.header.get_or_insert(HashMap::new()).insert(header.0, header.1);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Send a message.
+    pub fn send(mut self, m: Message) -> Self {
+        self.steps.push(Step::Send(m));
+        self
+    }
+
+    /// Send a text message.
+    pub fn send_text<V: Into<Value>>(self, text: V) -> Self {
+        self.send(Message::Text(text.into()))
+    }
+
+    /// Send a JSON message.
+    pub fn send_json(self, v: Value) -> Self {
+        self.send(Message::Json(v))
+    }
+
+    /// Send a binary message.
+    pub fn send_binary<B: Into<bytes::Bytes>>(self, b: B) -> Self {
+        self.send(Message::Binary(b.into()))
+    }
+
+    /// Wait for a matching message.
+    pub fn wait(mut self, w: Wait<'a>) -> Self {
+        self.steps.push(Step::Wait(w));
+        self
+    }
+
+    /// Specify an expect condition for task.
+    pub fn expect(mut self, tup: (&'a str, Value)) -> Self {
+        self.expect = Some(tup);
+        self
+    }
+
+    /// Build an WebSocketTask use this builder.
+    pub fn build(self) -> WebSocketTask<'a> {
+        WebSocketTask { config: self, task_id: crate::faker::uuid_v4() }
+    }
+}
+
+impl Default for WebSocketTaskBuilder<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,165 @@
+/// TLS protocol version.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TlsVersion {
+    Tls1_0,
+    Tls1_1,
+    Tls1_2,
+    Tls1_3,
+}
+
+impl From<TlsVersion> for reqwest::tls::Version {
+    fn from(val: TlsVersion) -> Self {
+        match val {
+            TlsVersion::Tls1_0 => reqwest::tls::Version::TLS_1_0,
+            TlsVersion::Tls1_1 => reqwest::tls::Version::TLS_1_1,
+            TlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+            TlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+        }
+    }
+}
+
+/// Client certificate.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientIdentity {
+    /// PEM certificate chain file and PKCS#8 PEM key file.
+    Pem { cert: String, key: String },
+    /// PKCS#12 archive file with password.
+    Pkcs12 { path: String, password: String },
+}
+
+/// TLS options for HTTP sessions, HTTP tasks and database connections.
+///
+/// Database connections support `root_certs` and `accept_invalid_certs` only,
+/// PostgreSQL maps them to `sslmode=verify-full` or `require` and MySQL to `VERIFY_IDENTITY` or `REQUIRED`.
+/// `identity` and `min_version` fail the database connection, DER root certificates are not supported,
+/// MSSQL doesn't support TLS at all.
+/// # Example
+///
+/// ```
+/// use anole::tls::{TlsOption, TlsVersion};
+///
+/// let tls = TlsOption::builder()
+///     .root_cert("certs/internal-ca.pem")
+///     .client_pem("certs/client.pem", "certs/client.key")
+///     .min_version(TlsVersion::Tls1_2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TlsOption {
+    pub root_certs: Vec<String>,
+    pub identity: Option<ClientIdentity>,
+    pub min_version: Option<TlsVersion>,
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsOption {
+
+    pub fn builder() -> Self {
+        TlsOption::default()
+    }
+
+    /// Trust an extra root certificate file, PEM (may contain several certificates) or DER.
+    pub fn root_cert(mut self, path: &str) -> Self {
+        self.root_certs.push(path.to_string());
+        self
+    }
+
+    /// Use client certificate from PEM certificate and PKCS#8 key files.
+    pub fn client_pem(mut self, cert: &str, key: &str) -> Self {
+        self.identity = Some(ClientIdentity::Pem { cert: cert.to_string(), key: key.to_string() });
+        self
+    }
+
+    /// Use client certificate from PKCS#12 file.
+    pub fn client_pkcs12(mut self, path: &str, password: &str) -> Self {
+        self.identity = Some(ClientIdentity::Pkcs12 { path: path.to_string(), password: password.to_string() });
+        self
+    }
+
+    pub fn min_version(mut self, version: TlsVersion) -> Self {
+        self.min_version = Some(version);
+        self
+    }
+
+    /// Accept invalid certificates and hostnames. Only for testing environments.
+    pub fn accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.accept_invalid_certs = accept;
+        self
+    }
+
+    pub(crate) fn apply_http(&self, mut builder: reqwest::ClientBuilder) -> crate::Result<reqwest::ClientBuilder> {
+        for path in &self.root_certs {
+            let content = read(path)?;
+            let certs = if is_pem(&content) {
+                reqwest::Certificate::from_pem_bundle(&content)
+            } else {
+                reqwest::Certificate::from_der(&content).map(|c| vec![c])
+            };
+            match certs {
+                Ok(certs) => {
+                    for c in certs {
+                        builder = builder.add_root_certificate(c);
+                    }
+                },
+                Err(e) => return Err(crate::error::create_client(e.into()))
+            }
+        }
+        if let Some(ref identity) = self.identity {
+            let identity = match identity {
+                ClientIdentity::Pem { cert, key } => reqwest::Identity::from_pkcs8_pem(&read(cert)?, &read(key)?),
+                ClientIdentity::Pkcs12 { path, password } => reqwest::Identity::from_pkcs12_der(&read(path)?, password),
+            };
+            match identity {
+                Ok(i) => builder = builder.identity(i),
+                Err(e) => return Err(crate::error::create_client(e.into()))
+            }
+        }
+        if let Some(version) = self.min_version {
+            builder = builder.min_tls_version(version.into());
+        }
+        if self.accept_invalid_certs {
+            builder = builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true);
+        }
+        Ok(builder)
+    }
+
+    /// Concatenated PEM root certificates for database drivers.
+    pub(crate) fn root_certs_pem(&self) -> crate::Result<Option<Vec<u8>>> {
+        if self.root_certs.is_empty() {
+            return Ok(None);
+        }
+        let mut pem = vec![];
+        for path in &self.root_certs {
+            let content = read(path)?;
+            if !is_pem(&content) {
+                return Err(crate::error::create_client(format!("{} is not a PEM certificate", path).into()));
+            }
+            pem.extend(content);
+            pem.push(b'\n');
+        }
+        Ok(Some(pem))
+    }
+
+    /// Database drivers only support root certificates and verification mode.
+    pub(crate) fn check_db_support(&self) -> crate::Result<()> {
+        if self.identity.is_some() {
+            return Err(crate::error::create_client("client certificate is not supported for database connections".into()));
+        }
+        if self.min_version.is_some() {
+            return Err(crate::error::create_client("min TLS version is not supported for database connections".into()));
+        }
+        Ok(())
+    }
+}
+
+fn read(path: &str) -> crate::Result<Vec<u8>> {
+    match std::fs::read(path) {
+        Ok(c) => Ok(c),
+        Err(e) => Err(crate::error::io(e.into()))
+    }
+}
+
+fn is_pem(content: &[u8]) -> bool {
+    String::from_utf8_lossy(content).contains("-----BEGIN")
+}
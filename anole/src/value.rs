@@ -1,13 +1,14 @@
 
-use serde::Serialize;
-use sqlx::types::time::{self, Date, Time};
+use std::collections::BTreeMap;
 
-use crate::{context::Context, error};
+use serde::{Serialize, ser::{SerializeSeq, SerializeMap}};
+use sqlx::types::{time::{self, Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset}, Decimal};
+
+use crate::{context::Context, error, store::Store};
 
 
 /// Represents any capture support value.
 #[derive(Debug, Clone)]
-#[derive(std::cmp::PartialEq)]
 pub enum Value {
     I32(i32),
     U32(u32),
@@ -18,6 +19,18 @@ pub enum Value {
     Str(String),
     Date(time::Date),
     Time(time::Time),
+    /// Arbitrary precision decimal, equal to decimals of the same value regardless of scale and to their string, e.g. `12.50` equals `"12.5"`.
+    Decimal(Decimal),
+    /// Date time with offset, equal to date times of the same instant and to their RFC 3339 string, e.g. `"2022-03-04T05:06:07Z"`.
+    DateTime(OffsetDateTime),
+    /// Binary data, rendered as lowercase hex string.
+    Bytes(Vec<u8>),
+    /// Array, captured from JSON arrays, rendered as compact JSON.
+    Array(Vec<Value>),
+    /// Object, captured from JSON objects, rendered as compact JSON.
+    Object(BTreeMap<String, Value>),
+    /// SQL NULL, bound as a NULL query argument.
+    Null,
 }
 
 impl Value {
@@ -103,6 +116,10 @@ impl Value {
                 Ok(f) => Ok(f),
                 Err(e) => Err(error::parse_value(e.into())),
             },
+            Self::Decimal(d) => match d.to_string().parse::<f64>() {
+                Ok(f) => Ok(f),
+                Err(e) => Err(error::parse_value(e.into())),
+            },
             _ => Err(error::unimplement("can not convert to f64"))
         }
     }
@@ -136,6 +153,11 @@ impl Value {
             Self::F64(f) => f.to_string(),
             Self::Date(d) => d.to_string(),
             Self::Time(t) => t.to_string(),
+            Self::Decimal(d) => d.to_string(),
+            Self::DateTime(t) => format_datetime(t),
+            Self::Bytes(b) => hex(b),
+            Self::Array(_) | Self::Object(_) => serde_json::to_string(self).unwrap_or_default(),
+            Self::Null => "null".to_string(),
         }
     }
 
@@ -155,6 +177,70 @@ impl Value {
         }
     }
 
+    /// Create an Object value from key-value pairs.
+    pub fn object<K, V, I>(items: I) -> Value where K: Into<String>, V: Into<Value>, I: IntoIterator<Item = (K, V)> {
+        Value::Object(items.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+    }
+
+    /// Create an Array value from items.
+    pub fn array<V, I>(items: I) -> Value where V: Into<Value>, I: IntoIterator<Item = V> {
+        Value::Array(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Get nested value by capture syntax path, e.g. `data.list|0.name`.
+    pub fn get_path(&self, path: &str) -> Option<&Value> {
+        let mut cur_value = self;
+        for k in path.split('.') {
+            let (is_arr, k, idx) = try_as_array(k);
+            if !k.is_empty() {
+                cur_value = match cur_value {
+                    Self::Object(m) => m.get(k)?,
+                    _ => return None,
+                };
+            }
+            if is_arr {
+                cur_value = match cur_value {
+                    Self::Array(a) => a.get(idx)?,
+                    _ => return None,
+                };
+            }
+        }
+        Some(cur_value)
+    }
+
+    /// Replace wildcards with store values recursively.
+    /// A wildcard may address into a captured structured value, e.g. `:user.roles|0`.
+    /// Unresolved wildcards are kept as is.
+    pub fn resolve(&self, store: &Store) -> Value {
+        match self {
+            Self::Array(a) => Value::Array(a.iter().map(|v| v.resolve(store)).collect()),
+            Self::Object(m) => Value::Object(m.iter().map(|(k, v)| (k.to_owned(), v.resolve(store))).collect()),
+            _ => {
+                if let Some(wildcard) = self.as_wildcard() {
+                    if let Some(v) = store.lookup(&wildcard) {
+                        return v;
+                    }
+                }
+                self.to_owned()
+            }
+        }
+    }
+
+    /// Equality of expect checks, decimals and date times also equal their string form,
+    /// e.g. `Value::Decimal` of `12.50` matches `Value::from("12.5")`. Arrays and objects compare element-wise,
+    /// and match their JSON text as they did when captured as strings, e.g. `Value::from("[1, 2]")`.
+    pub fn loose_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (v @ (Self::Array(_) | Self::Object(_)), Self::Str(s)) | (Self::Str(s), v @ (Self::Array(_) | Self::Object(_))) =>
+                serde_json::from_str::<serde_json::Value>(s).is_ok_and(|j| Value::from(&j).loose_eq(v)),
+            (Self::Decimal(d), Self::Str(s)) | (Self::Str(s), Self::Decimal(d)) => s.parse::<Decimal>().is_ok_and(|v| v == *d),
+            (Self::DateTime(t), Self::Str(s)) | (Self::Str(s), Self::DateTime(t)) => parse_datetime(s) == Some(*t),
+            (Self::Array(a), Self::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.loose_eq(y)),
+            (Self::Object(a), Self::Object(b)) => a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| v.loose_eq(w))),
+            _ => self == other,
+        }
+    }
+
     /// If Value is wildcard, returns real String. Returns None otherwise.
     pub fn as_wildcard(&self) -> Option<String> {
         match self {
@@ -169,6 +255,68 @@ impl Value {
     }
 }
 
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::I32(a), Self::I32(b)) => a == b,
+            (Self::U32(a), Self::U32(b)) => a == b,
+            (Self::I64(a), Self::I64(b)) => a == b,
+            (Self::U64(a), Self::U64(b)) => a == b,
+            (Self::F64(a), Self::F64(b)) => a == b,
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Str(a), Self::Str(b)) => a == b,
+            (Self::Date(a), Self::Date(b)) => a == b,
+            (Self::Time(a), Self::Time(b)) => a == b,
+            (Self::Decimal(a), Self::Decimal(b)) => a == b,
+            (Self::DateTime(a), Self::DateTime(b)) => a == b,
+            (Self::Bytes(a), Self::Bytes(b)) => a == b,
+            (Self::Array(a), Self::Array(b)) => a == b,
+            (Self::Object(a), Self::Object(b)) => a == b,
+            (Self::Null, Self::Null) => true,
+            _ => false,
+        }
+    }
+}
+
+/// RFC 3339 string in UTC, e.g. `2022-03-04T05:06:07Z`, fraction of second is kept if not zero.
+pub(crate) fn format_datetime(t: &OffsetDateTime) -> String {
+    let t = t.to_offset(UtcOffset::UTC);
+    match t.nanosecond() {
+        0 => t.format("%Y-%m-%dT%H:%M:%SZ"),
+        n => format!("{}.{}Z", t.format("%Y-%m-%dT%H:%M:%S"), format!("{:09}", n).trim_end_matches('0')),
+    }
+}
+
+/// Parse RFC 3339 string, e.g. `2022-03-04T13:06:07.25+08:00`, a space may separate date and time.
+/// Date times without offset are in UTC.
+pub(crate) fn parse_datetime(s: &str) -> Option<OffsetDateTime> {
+    let (datetime, offset) = match s.strip_suffix(['Z', 'z']) {
+        Some(d) => (d, UtcOffset::UTC),
+        None => match (s.get(..s.len().saturating_sub(6)), s.get(s.len().saturating_sub(6)..)) {
+            (Some(d), Some(o)) if (o.starts_with('+') || o.starts_with('-')) && o.get(3..4) == Some(":") => {
+                match UtcOffset::parse(o.replace(':', ""), "%z") {
+                    Ok(o) => (d, o),
+                    Err(_) => return None
+                }
+            },
+            _ => (s, UtcOffset::UTC)
+        }
+    };
+    let (datetime, fraction) = datetime.split_once('.').unwrap_or((datetime, ""));
+    let nanos = match fraction {
+        "" => 0,
+        f if f.len() <= 9 && f.bytes().all(|b| b.is_ascii_digit()) => format!("{:0<9}", f).parse::<u32>().ok()?,
+        _ => return None
+    };
+    let t = PrimitiveDateTime::parse(datetime.replacen(' ', "T", 1), "%Y-%m-%dT%H:%M:%S").ok()?;
+    let time = Time::try_from_hms_nano(t.hour(), t.minute(), t.second(), nanos).ok()?;
+    Some(PrimitiveDateTime::new(t.date(), time).assume_offset(offset))
+}
+
+fn hex(b: &[u8]) -> String {
+    b.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
 impl From<i32> for Value {
     fn from(i: i32) -> Self {
         Value::I32(i)
@@ -205,16 +353,57 @@ impl From<String> for Value {
     }
 }
 
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::I64(i)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(u: u64) -> Self {
+        Value::U64(u)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(v: Vec<Value>) -> Self {
+        Value::Array(v)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(m: BTreeMap<String, Value>) -> Self {
+        Value::Object(m)
+    }
+}
+
 impl From<&serde_json::Value> for Value {
     fn from(val: &serde_json::Value) -> Self {
-        if val.is_boolean() {
+        if val.is_null() {
+            Value::Null
+        } else if val.is_boolean() {
             Value::Bool(val.as_bool().unwrap())
-        } else if val.is_i64() {
-            Value::I32(val.as_i64().unwrap() as i32)
+        } else if let Some(i) = val.as_i64() {
+            match i32::try_from(i) {
+                Ok(i) => Value::I32(i),
+                Err(_) => Value::I64(i)
+            }
+        } else if let Some(u) = val.as_u64() {
+            Value::U64(u)
         } else if val.is_f64() {
             Value::F64(val.as_f64().unwrap())
         } else if let Some(vv) = val.as_str() {
             Value::Str(vv.to_string())
+        } else if let Some(arr) = val.as_array() {
+            Value::Array(arr.iter().map(Value::from).collect())
+        } else if let Some(obj) = val.as_object() {
+            Value::Object(obj.iter().map(|(k, v)| (k.to_owned(), Value::from(v))).collect())
         } else {
             Value::Str(format!("{}", val))
         }
@@ -226,7 +415,8 @@ pub fn parse_json_value(value: &serde_json::Value, key: String) -> Option<serde_
     let mut cur_value = value;
     for k in keys {
         let (is_arr, k, idx) = try_as_array(k);
-        cur_value = &cur_value[k];
+        // missing keys are None, keys of null value are Some(Null).
+        cur_value = cur_value.get(k)?;
         if is_arr {
             if cur_value.is_array() && cur_value.as_array().unwrap().len() > idx {
                 cur_value = &cur_value[idx];
@@ -265,6 +455,24 @@ impl Serialize for Value {
             Value::Str(s) => serializer.serialize_str(s),
             Value::Date(d) => serializer.serialize_str(&d.to_string()),
             Value::Time(t) => serializer.serialize_u16(t.millisecond()),
+            Value::Decimal(d) => serializer.serialize_str(&d.to_string()),
+            Value::DateTime(t) => serializer.serialize_str(&format_datetime(t)),
+            Value::Bytes(b) => serializer.serialize_str(&hex(b)),
+            Value::Array(a) => {
+                let mut seq = serializer.serialize_seq(Some(a.len()))?;
+                for v in a {
+                    seq.serialize_element(v)?;
+                }
+                seq.end()
+            }
+            Value::Null => serializer.serialize_none(),
+            Value::Object(m) => {
+                let mut map = serializer.serialize_map(Some(m.len()))?;
+                for (k, v) in m {
+                    map.serialize_entry(k, v)?;
+                }
+                map.end()
+            }
         }
     }
 }
@@ -273,9 +481,19 @@ pub enum Body {
     File(String),
     Raw(bytes::Bytes),
     Replace(String, Vec<Value>),
+    /// JSON body, wildcards in the tree are replaced with store values.
+    Json(Value),
 }
 
 impl Body {
+    /// Returns the Content-Type implied by this body.
+    pub fn content_type(&self) -> Option<&'static str> {
+        match self {
+            Self::Json(_) => Some("application/json"),
+            _ => None,
+        }
+    }
+
     pub fn as_bytes(&self, ctx: &mut Context) -> Option<bytes::Bytes> {
         match self {
             Self::Raw(b) => Some(b.to_owned()),
@@ -296,6 +514,87 @@ impl Body {
                 }
                 Some(tmpl.into())
             }
+            Self::Json(v) => {
+                match serde_json::to_vec(&v.resolve(&ctx.store)) {
+                    Ok(b) => Some(bytes::Bytes::from(b)),
+                    Err(_) => None
+                }
+            }
+        }
+    }
+}
+
+enum PartSource {
+    Text(Value),
+    File(String),
+    Bytes(bytes::Bytes),
+}
+
+/// A part of multipart/form-data body.
+pub struct Part {
+    source: PartSource,
+    file_name: Option<String>,
+    mime: Option<String>,
+}
+
+impl Part {
+    /// Text field, wildcard is replaced with store value.
+    pub fn text<V: Into<Value>>(v: V) -> Self {
+        Part { source: PartSource::Text(v.into()), file_name: None, mime: None }
+    }
+
+    /// File part read from disk, file name and mime are guessed from path unless specified.
+    pub fn file(path: &str) -> Self {
+        let file_name = std::path::Path::new(path).file_name().map(|f| f.to_string_lossy().to_string());
+        Part { source: PartSource::File(path.to_string()), file_name, mime: None }
+    }
+
+    /// File part from generated bytes.
+    pub fn bytes<B: Into<bytes::Bytes>>(b: B) -> Self {
+        Part { source: PartSource::Bytes(b.into()), file_name: None, mime: None }
+    }
+
+    /// Specify file name of this part.
+    pub fn file_name(mut self, name: &str) -> Self {
+        self.file_name = Some(name.to_string());
+        self
+    }
+
+    /// Specify content type of this part.
+    pub fn mime(mut self, mime: &str) -> Self {
+        self.mime = Some(mime.to_string());
+        self
+    }
+
+    pub(crate) async fn to_part(&self, ctx: &Context) -> crate::Result<reqwest::multipart::Part> {
+        let mut part = match &self.source {
+            PartSource::Text(v) => reqwest::multipart::Part::text(v.resolve(&ctx.store).as_str()),
+            PartSource::Bytes(b) => reqwest::multipart::Part::bytes(b.to_vec()),
+            PartSource::File(path) => {
+                let content = match tokio::fs::read(path).await {
+                    Ok(c) => c,
+                    Err(e) => return Err(error::io(e.into()))
+                };
+                let mut part = reqwest::multipart::Part::bytes(content);
+                if self.mime.is_none() {
+                    let mime = mime_guess::from_path(path).first_or_octet_stream();
+                    part = match part.mime_str(mime.as_ref()) {
+                        Ok(p) => p,
+                        Err(e) => return Err(error::parse_value(e.into()))
+                    };
+                }
+                part
+            }
+        };
+        if let Some(ref name) = self.file_name {
+            part = part.file_name(name.to_owned());
+        }
+        if let Some(ref mime) = self.mime {
+            part = match part.mime_str(mime) {
+                Ok(p) => p,
+                Err(e) => return Err(error::parse_value(e.into()))
+            };
         }
+        Ok(part)
     }
 }
@@ -0,0 +1,165 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, Method, auth::{Auth, OAuth2}, session::{HttpSessionOption, DEFAULT_SESSION}}, capture, value::Value};
+
+#[tokio::test]
+async fn test_static_auth() {
+    let addr = common::serve(|req| {
+        let line = req.request_line();
+        let ok = if line.starts_with("GET /basic ") {
+            // anole:secret
+            req.header("authorization").as_deref() == Some("Basic YW5vbGU6c2VjcmV0")
+        } else if line.starts_with("GET /key?api_key=k1 ") {
+            true
+        } else if line.starts_with("GET /header ") {
+            req.header("x-api-key").as_deref() == Some("k1")
+        } else if line.starts_with("GET /bearer ") {
+            req.header("authorization").as_deref() == Some("Bearer k1")
+        } else {
+            false
+        };
+        common::json(&format!(r#"{{"ok": {}, "key": "k1"}}"#, ok))
+    });
+    let urls = ["basic", "key", "header", "bearer", "login"].map(|p| format!("http://{}/{}", addr, p));
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[4])
+            .capture(vec![capture::json("key", "key")])
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[0])
+            .auth(Auth::basic("anole", "secret"))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[1])
+            .auth(Auth::api_key_query("api_key", ":key"))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[2])
+            .auth(Auth::api_key_header("x-api-key", ":key"))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[3])
+            .auth(Auth::bearer(":key"))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 5);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+static TOKEN_ISSUED: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn test_oauth2_refresh() {
+    let addr = common::serve(|req| {
+        if req.request_line().starts_with("POST /token ") {
+            let body = req.body_str();
+            assert!(body.contains("client_id=id") && body.contains("client_secret=secret"), "{}", body);
+            let n = TOKEN_ISSUED.fetch_add(1, Ordering::SeqCst) + 1;
+            let grant_ok = if n == 1 {
+                body.contains("grant_type=client_credentials") && body.contains("scope=orders")
+            } else {
+                body.contains("grant_type=refresh_token") && body.contains("refresh_token=r1")
+            };
+            assert!(grant_ok, "{}", body);
+            return common::json(&format!(r#"{{"access_token": "t{}", "refresh_token": "r1", "expires_in": 3600}}"#, n));
+        }
+        // first token is revoked by server
+        if req.header("authorization").as_deref() == Some("Bearer t2") {
+            return common::json(r#"{"ok": true}"#);
+        }
+        common::response("401 Unauthorized", &[], "")
+    });
+    let token_url = format!("http://{}/token", addr);
+    let api_url = format!("http://{}/orders", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http_session(DEFAULT_SESSION, HttpSessionOption::builder()
+            .auth(Auth::oauth2(OAuth2::client_credentials(&token_url, "id", "secret").scope("orders").save_key("token"))))
+        .with_http(HttpTaskBuilder::new()
+            .url(&api_url)
+            .method(Method::Post)
+            .json(Value::object([("token", ":token")]))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&api_url)
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+    // second task uses cached token
+    assert_eq!(TOKEN_ISSUED.load(Ordering::SeqCst), 2);
+}
+
+static KEPT_TOKEN_ISSUED: AtomicUsize = AtomicUsize::new(0);
+static KEPT_TOKEN_USED: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn test_oauth2_refresh_keeps_refresh_token() {
+    let addr = common::serve(|req| {
+        if req.request_line().starts_with("POST /token ") {
+            let body = req.body_str();
+            let n = KEPT_TOKEN_ISSUED.fetch_add(1, Ordering::SeqCst) + 1;
+            // only the first response has a refresh token, later refreshes still send it
+            if n == 1 && body.contains("grant_type=client_credentials") {
+                return common::json(r#"{"access_token": "t1", "refresh_token": "r1", "expires_in": 3600}"#);
+            }
+            if body.contains("grant_type=refresh_token") && body.contains("refresh_token=r1") {
+                return common::json(&format!(r#"{{"access_token": "t{}", "expires_in": 3600}}"#, n));
+            }
+            return common::response("400 Bad Request", &[], "");
+        }
+        // every refreshed token is accepted once
+        let used = KEPT_TOKEN_USED.load(Ordering::SeqCst);
+        let issued = KEPT_TOKEN_ISSUED.load(Ordering::SeqCst);
+        if issued > 1 && used < issued && req.header("authorization") == Some(format!("Bearer t{}", issued)) {
+            KEPT_TOKEN_USED.store(issued, Ordering::SeqCst);
+            return common::json(r#"{"ok": true}"#);
+        }
+        common::response("401 Unauthorized", &[], "")
+    });
+    let token_url = format!("http://{}/token", addr);
+    let api_url = format!("http://{}/orders", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http_session(DEFAULT_SESSION, HttpSessionOption::builder()
+            .auth(Auth::oauth2(OAuth2::client_credentials(&token_url, "id", "secret"))))
+        .with_http(HttpTaskBuilder::new()
+            .url(&api_url)
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&api_url)
+            .capture(vec![capture::json("ok", "ok2")])
+            .expect(("ok2", Value::Bool(true)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+    assert_eq!(KEPT_TOKEN_ISSUED.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, session::{HttpSessionOption, RedirectPolicy, DEFAULT_SESSION}}, capture, value::Value};
+
+#[tokio::test]
+async fn test_redirect() {
+    let addr = common::serve(|req| {
+        if req.request_line().starts_with("GET /login ") {
+            return common::response("302 Found", &[("location", "/home")], "");
+        }
+        common::json(r#"{"page": "home"}"#)
+    });
+    let url = format!("http://{}/login", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .redirect(RedirectPolicy::None)
+            .expect_status(302)
+            .capture(vec![capture::header("location", "location")])
+            .expect(("location", Value::from("/home")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .capture(vec![capture::json("page", "page")])
+            .expect(("page", Value::from("home")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .redirect(RedirectPolicy::None)
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, false]);
+}
+
+#[tokio::test]
+async fn test_proxy_and_resolve() {
+    let proxy = common::serve(|req| {
+        let ok = req.request_line().starts_with("GET http://api.anole.test/v1 ");
+        common::json(&format!(r#"{{"via": "proxy", "ok": {}}}"#, ok))
+    });
+    let backend = common::serve(|req| {
+        let ok = req.header("host").map(|h| h.starts_with("api.anole.test:")).unwrap_or(false);
+        common::json(&format!(r#"{{"via": "backend", "ok": {}}}"#, ok))
+    });
+    let proxy_url = format!("http://{}", proxy);
+    let backend_url = format!("http://api.anole.test:{}/v1", backend.port());
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http_session(DEFAULT_SESSION, HttpSessionOption::builder().resolve("api.anole.test", "127.0.0.1"))
+        .with_http(HttpTaskBuilder::new()
+            .url(&backend_url)
+            .capture(vec![capture::json("via", "via"), capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url("http://api.anole.test/v1")
+            .proxy(&proxy_url)
+            .capture(vec![capture::json("via", "via")])
+            .expect(("via", Value::from("proxy")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
@@ -0,0 +1,96 @@
+#![allow(dead_code)]
+
+use std::{io::{Read, Write}, net::{SocketAddr, TcpListener, TcpStream}, sync::{self, Arc, mpsc::Receiver}, thread};
+
+use anole::report::{ReportItem, Reporter};
+
+/// Raw HTTP request received by test server.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub head: String,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn request_line(&self) -> &str {
+        self.head.lines().next().unwrap_or("")
+    }
+
+    pub fn header(&self, name: &str) -> Option<String> {
+        self.head.lines().skip(1).find_map(|l| {
+            let (k, v) = l.split_once(':')?;
+            if k.trim().eq_ignore_ascii_case(name) {
+                return Some(v.trim().to_string());
+            }
+            None
+        })
+    }
+
+    pub fn body_str(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+}
+
+/// Start a local HTTP/1.1 server answering each request with `handler`.
+pub fn serve<F>(handler: F) -> SocketAddr where F: Fn(&Request) -> String + Send + Sync + 'static {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let handler = Arc::new(handler);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            let handler = handler.clone();
+            thread::spawn(move || {
+                while let Some(req) = read_request(&mut stream) {
+                    let rsp = handler(&req);
+                    if stream.write_all(rsp.as_bytes()).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    addr
+}
+
+fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = vec![];
+    let mut byte = [0u8; 1];
+    while !buf.ends_with(b"\r\n\r\n") {
+        if stream.read(&mut byte).ok()? == 0 {
+            return None;
+        }
+        buf.push(byte[0]);
+    }
+    let head = String::from_utf8_lossy(&buf).to_string();
+    let mut req = Request { head, body: vec![] };
+    let len = req.header("content-length").and_then(|l| l.parse::<usize>().ok()).unwrap_or(0);
+    req.body = vec![0u8; len];
+    stream.read_exact(&mut req.body).ok()?;
+    Some(req)
+}
+
+/// Build a raw HTTP response.
+pub fn response(status: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let mut rsp = format!("HTTP/1.1 {}\r\ncontent-length: {}\r\n", status, body.len());
+    for (k, v) in headers {
+        rsp.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    rsp.push_str("\r\n");
+    rsp.push_str(body);
+    rsp
+}
+
+/// Build a raw `200 OK` JSON response.
+pub fn json(body: &str) -> String {
+    response("200 OK", &[("content-type", "application/json")], body)
+}
+
+/// Reporter collecting report items.
+pub fn reporter() -> (Box<dyn Reporter>, Receiver<ReportItem>) {
+    let (sender, recv) = sync::mpsc::channel::<ReportItem>();
+    (Box::new(anole::report::StdReporter::new(sender)), recv)
+}
@@ -0,0 +1,111 @@
+mod common;
+
+#[cfg(test)]
+mod deserializer {
+    use anole::task::http::Deserializer;
+
+    #[test]
+    fn test_resolve_content_type() {
+        let auto = Deserializer::Auto;
+        assert_eq!(auto.resolve(Some("application/json; charset=utf-8"), ""), Deserializer::Json);
+        assert_eq!(auto.resolve(Some("application/problem+json"), ""), Deserializer::Json);
+        assert_eq!(auto.resolve(Some("text/xml"), ""), Deserializer::Xml);
+        assert_eq!(auto.resolve(Some("application/atom+xml"), ""), Deserializer::Xml);
+        assert_eq!(auto.resolve(Some("text/html; charset=utf-8"), ""), Deserializer::Html);
+    }
+
+    #[test]
+    fn test_resolve_sniff() {
+        let auto = Deserializer::Auto;
+        assert_eq!(auto.resolve(None, r#" {"code": 0}"#), Deserializer::Json);
+        assert_eq!(auto.resolve(Some("text/plain"), "[1, 2]"), Deserializer::Json);
+        assert_eq!(auto.resolve(None, "<?xml version=\"1.0\"?><rsp></rsp>"), Deserializer::Xml);
+        assert_eq!(auto.resolve(Some("application/octet-stream"), "<!DOCTYPE html><html></html>"), Deserializer::Html);
+        assert_eq!(auto.resolve(None, "{not json"), Deserializer::Text);
+        assert_eq!(auto.resolve(None, "pong"), Deserializer::Text);
+    }
+
+    #[test]
+    fn test_resolve_explicit() {
+        assert_eq!(Deserializer::Xml.resolve(Some("application/json"), "{}"), Deserializer::Xml);
+    }
+}
+
+
+#[tokio::test]
+async fn test_auto_capture() {
+    use anole::{engine::Engine, task::http::HttpTaskBuilder, value::Value, capture};
+
+    let addr = common::serve(|req| {
+        let html = ("content-type", "text/html; charset=utf-8");
+        match req.request_line().split(' ').nth(1).unwrap_or_default() {
+            "/json" => common::json(r#"{"title": "Home"}"#),
+            "/xml" => common::response("200 OK", &[("content-type", "text/xml")], "<rsp><title>Home</title></rsp>"),
+            "/xhtml" => common::response("200 OK", &[html], "<!DOCTYPE html><html><body><h1>Home</h1><br/></body></html>"),
+            _ => common::response("200 OK", &[html], "<!DOCTYPE html><html><body><h1>Home</h1><div><br></div><p>Welcome</p></body></html>"),
+        }
+    });
+    let urls = ["json", "xml", "xhtml", "html"].map(|p| format!("http://{}/{}", addr, p));
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[0])
+            .capture(vec![capture::json("title", "json_title")])
+            .expect(("json_title", Value::from("Home")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[1])
+            .capture(vec![capture::xml("title", "xml_title")])
+            .expect(("xml_title", Value::from("Home")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[2])
+            .capture(vec![capture::xml("body.h1", "xhtml_title")])
+            .expect(("xhtml_title", Value::from("Home")))
+            .build())
+        // HTML which isn't XML is captured until `</div>` closing the unclosed `<br>`
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[3])
+            .capture(vec![capture::xml("body.h1", "html_title"), capture::xml("body.p", "html_welcome")])
+            .expect(("html_title", Value::from("Home")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&urls[3])
+            .expect(("html_welcome", Value::from("Welcome")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, true, true, false]);
+}
+
+#[tokio::test]
+async fn test_json_null_capture() {
+    use anole::{engine::Engine, task::http::HttpTaskBuilder, value::Value, capture};
+
+    let addr = common::serve(|_| common::json(r#"{"parent": null, "children": [null]}"#));
+    let url = format!("http://{}/", addr);
+    let check = |key, expect| HttpTaskBuilder::new().url(&url).expect((key, expect)).build();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .capture(vec![
+                capture::json("parent", "parent"),
+                capture::json("children|0", "child"),
+                capture::json("children", "children"),
+                capture::json("missing", "missing"),
+            ])
+            .expect(("parent", Value::Null))
+            .build())
+        .with_http(check("child", Value::Null))
+        .with_http(check("children", Value::array([Value::Null])))
+        // missing paths are not captured
+        .with_http(check("missing", Value::Null))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, true, false]);
+}
@@ -0,0 +1,7 @@
+-- users and orders used by script tests, it's safe to run twice.
+CREATE TABLE IF NOT EXISTS users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS orders (id INTEGER PRIMARY KEY, user_id INTEGER NOT NULL, note TEXT);
+
+/* seed rows, ':name' in comments is kept */
+INSERT INTO users (id, name) VALUES (1, 'anole'), (2, 'O''Brien');
+INSERT INTO orders (user_id, note) VALUES (1, 'first'), (2, 'time 12:30:00');
@@ -0,0 +1,90 @@
+mod common;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, graphql::GraphQL}, capture, value::Value};
+
+fn graphql_server() -> std::net::SocketAddr {
+    common::serve(|req| {
+        if !req.request_line().starts_with("POST /graphql ")
+            || req.header("content-type").as_deref() != Some("application/json") {
+            return common::response("400 Bad Request", &[], "");
+        }
+        let body: serde_json::Value = serde_json::from_slice(&req.body).unwrap();
+        let query = body["query"].as_str().unwrap_or("");
+        if query.contains("viewer") {
+            return common::json(r#"{"data": {"viewer": {"id": "u-1", "roles": ["admin"]}}}"#);
+        }
+        if query.contains("createOrder") {
+            let ok = body["operationName"] == "Create" && body["variables"]["userId"] == "u-1";
+            return common::json(&format!(r#"{{"data": {{"createOrder": {{"id": 42, "ok": {}}}}}}}"#, ok));
+        }
+        common::json(r#"{"data": {"order": null}, "errors": [{"message": "order not found", "extensions": {"code": "NOT_FOUND"}}]}"#)
+    })
+}
+
+#[tokio::test]
+async fn test_graphql_variables_and_capture() {
+    let addr = graphql_server();
+    let url = format!("http://{}/graphql", addr);
+    let path = std::env::temp_dir().join(format!("anole-graphql-{}.graphql", std::process::id()));
+    std::fs::write(&path, "mutation Create($userId: ID!) { createOrder(userId: $userId) { id ok } }").unwrap();
+    let document = path.to_str().unwrap().to_string();
+    let (reporter, recv) = common::reporter();
+    let r = Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::query("{ viewer { id roles } }")
+                .capture(vec![capture::json("viewer.id", "user_id")])
+                .save_data("me"))
+            .expect(("user_id", Value::from("u-1")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::file(&document)
+                .operation_name("Create")
+                .variable("userId", ":me.viewer.id")
+                .capture(vec![capture::json("createOrder.id", "order_id"), capture::json("createOrder.ok", "ok")]))
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .run().await;
+    let _ = std::fs::remove_file(&path);
+    r.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_graphql_errors() {
+    let addr = graphql_server();
+    let url = format!("http://{}/graphql", addr);
+    let query = "query { order(id: 1) { id } }";
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::query(query))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::query(query).expect_error_code("NOT_FOUND"))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::query(query).expect_error_code("NOT_FOUND").expect_error_code("FORBIDDEN"))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::query(query).allow_errors())
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .graphql(GraphQL::query("{ viewer { id } }").expect_error_code("NOT_FOUND"))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![false, true, false, true, false]);
+}
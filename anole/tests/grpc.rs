@@ -0,0 +1,317 @@
+#![allow(clippy::result_large_err)]
+
+mod common;
+
+use std::{convert::Infallible, net::SocketAddr};
+
+use anole::{engine::Engine, task::grpc::{GrpcTaskBuilder, Schema}, capture, value::Value};
+use prost::Message as _;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use prost_types::{field_descriptor_proto::{Label, Type}, DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet, MethodDescriptorProto, ServiceDescriptorProto};
+use tonic::{
+    body::BoxBody,
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    codegen::{http, tokio_stream::wrappers::TcpListenerStream, BoxFuture, Service},
+    server::{Grpc, NamedService, ServerStreamingService, UnaryService},
+    transport::{Body, Server},
+    Request, Response, Status,
+};
+
+fn field(name: &str, number: i32, t: Type, label: Label, type_name: Option<&str>) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        label: Some(label as i32),
+        r#type: Some(t as i32),
+        type_name: type_name.map(|t| t.to_string()),
+        json_name: None,
+        ..Default::default()
+    }
+}
+
+fn message(name: &str, fields: Vec<FieldDescriptorProto>) -> DescriptorProto {
+    DescriptorProto { name: Some(name.to_string()), field: fields, ..Default::default() }
+}
+
+/// Descriptor set of:
+///
+/// ```text
+/// package test.v1;
+/// message HelloRequest { string name = 1; int32 times = 2; }
+/// message Meta { string trace_id = 1; }
+/// message HelloReply { string message = 1; int64 seq = 2; Meta meta = 3; repeated string tags = 4; }
+/// service Greeter {
+///   rpc SayHello(HelloRequest) returns (HelloReply);
+///   rpc StreamHello(HelloRequest) returns (stream HelloReply);
+/// }
+/// ```
+fn descriptor_set() -> FileDescriptorSet {
+    let file = FileDescriptorProto {
+        name: Some("test/v1/greeter.proto".to_string()),
+        package: Some("test.v1".to_string()),
+        syntax: Some("proto3".to_string()),
+        message_type: vec![
+            message("HelloRequest", vec![
+                field("name", 1, Type::String, Label::Optional, None),
+                field("times", 2, Type::Int32, Label::Optional, None),
+            ]),
+            message("Meta", vec![field("trace_id", 1, Type::String, Label::Optional, None)]),
+            message("HelloReply", vec![
+                field("message", 1, Type::String, Label::Optional, None),
+                field("seq", 2, Type::Int64, Label::Optional, None),
+                field("meta", 3, Type::Message, Label::Optional, Some(".test.v1.Meta")),
+                field("tags", 4, Type::String, Label::Repeated, None),
+            ]),
+        ],
+        service: vec![ServiceDescriptorProto {
+            name: Some("Greeter".to_string()),
+            method: vec![
+                MethodDescriptorProto {
+                    name: Some("SayHello".to_string()),
+                    input_type: Some(".test.v1.HelloRequest".to_string()),
+                    output_type: Some(".test.v1.HelloReply".to_string()),
+                    ..Default::default()
+                },
+                MethodDescriptorProto {
+                    name: Some("StreamHello".to_string()),
+                    input_type: Some(".test.v1.HelloRequest".to_string()),
+                    output_type: Some(".test.v1.HelloReply".to_string()),
+                    server_streaming: Some(true),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    FileDescriptorSet { file: vec![file] }
+}
+
+struct DynamicCodec(MessageDescriptor);
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicCodec;
+    type Decoder = DynamicCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicCodec(self.0.clone())
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicCodec(self.0.clone())
+    }
+}
+
+impl Encoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst).map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+impl Decoder for DynamicCodec {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.0.clone(), src).map(Some).map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+#[derive(Clone)]
+struct Greeter {
+    pool: DescriptorPool,
+}
+
+impl Greeter {
+    fn reply(&self, request: &DynamicMessage, seq: i64) -> DynamicMessage {
+        let name = request.get_field_by_name("name").and_then(|v| v.as_str().map(|s| s.to_string())).unwrap_or_default();
+        let json = serde_json::json!({
+            "message": format!("hello {}", name),
+            "seq": seq,
+            "meta": {"trace_id": format!("trace-{}", seq)},
+            "tags": ["a", name],
+        });
+        DynamicMessage::deserialize(self.pool.get_message_by_name("test.v1.HelloReply").unwrap(), json).unwrap()
+    }
+}
+
+impl UnaryService<DynamicMessage> for Greeter {
+    type Response = DynamicMessage;
+    type Future = BoxFuture<Response<DynamicMessage>, Status>;
+
+    fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            if request.metadata().get("authorization").and_then(|v| v.to_str().ok()) != Some("Bearer t0k") {
+                return Err(Status::unauthenticated("missing token"));
+            }
+            let message = request.into_inner();
+            if message.get_field_by_name("name").and_then(|v| v.as_str().map(|s| s == "missing")).unwrap_or(false) {
+                return Err(Status::not_found("no such user"));
+            }
+            // int64 beyond i32, e.g. a millisecond timestamp
+            let seq = if message.get_field_by_name("name").and_then(|v| v.as_str().map(|s| s == "epoch")).unwrap_or(false) {
+                1_700_000_000_123
+            } else {
+                1
+            };
+            let mut rsp = Response::new(this.reply(&message, seq));
+            rsp.metadata_mut().insert("x-request-id", "req-1".parse().unwrap());
+            Ok(rsp)
+        })
+    }
+}
+
+#[derive(Clone)]
+struct StreamGreeter(Greeter);
+
+impl ServerStreamingService<DynamicMessage> for StreamGreeter {
+    type Response = DynamicMessage;
+    type ResponseStream = futures_util::stream::Iter<std::vec::IntoIter<Result<DynamicMessage, Status>>>;
+    type Future = BoxFuture<Response<Self::ResponseStream>, Status>;
+
+    fn call(&mut self, request: Request<DynamicMessage>) -> Self::Future {
+        let this = self.0.clone();
+        Box::pin(async move {
+            let message = request.into_inner();
+            let times = message.get_field_by_name("times").and_then(|v| v.as_i32()).unwrap_or(1);
+            let replies = (1..=times as i64).map(|seq| Ok(this.reply(&message, seq))).collect::<Vec<_>>();
+            Ok(Response::new(futures_util::stream::iter(replies)))
+        })
+    }
+}
+
+impl Service<http::Request<Body>> for Greeter {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+        let this = self.clone();
+        let codec = DynamicCodec(self.pool.get_message_by_name("test.v1.HelloRequest").unwrap());
+        Box::pin(async move {
+            match req.uri().path() {
+                "/test.v1.Greeter/SayHello" => Ok(Grpc::new(codec).unary(this, req).await),
+                "/test.v1.Greeter/StreamHello" => Ok(Grpc::new(codec).server_streaming(StreamGreeter(this), req).await),
+                _ => Ok(Status::unimplemented("").to_http()),
+            }
+        })
+    }
+}
+
+impl NamedService for Greeter {
+    const NAME: &'static str = "test.v1.Greeter";
+}
+
+async fn serve() -> SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let set = descriptor_set();
+    let greeter = Greeter { pool: DescriptorPool::from_file_descriptor_set(set.clone()).unwrap() };
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_file_descriptor_set(set)
+        .build()
+        .unwrap();
+    tokio::spawn(Server::builder()
+        .add_service(greeter)
+        .add_service(reflection)
+        .serve_with_incoming(TcpListenerStream::new(listener)));
+    addr
+}
+
+#[tokio::test]
+async fn test_grpc_unary() {
+    let addr = serve().await;
+    let url = format!("http://{}", addr);
+    let descriptor = std::env::temp_dir().join(format!("anole-grpc-{}.pb", std::process::id()));
+    std::fs::write(&descriptor, descriptor_set().encode_to_vec()).unwrap();
+    let descriptor = descriptor.to_str().unwrap().to_string();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter/SayHello")
+            .schema(Schema::DescriptorSet(&descriptor))
+            .metadata(("authorization", Value::from("Bearer t0k")))
+            .message(Value::object([("name", "anole")]))
+            .capture(vec![
+                capture::json("message", "message"),
+                capture::json("meta.trace_id", "trace_id"),
+                capture::json("tags|1", "user"),
+                capture::header("x-request-id", "request_id"),
+            ])
+            .expect(("message", Value::from("hello anole")))
+            .build())
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter.SayHello")
+            .metadata(("authorization", Value::from("Bearer t0k")))
+            .message(Value::object([("name", ":user")]))
+            .capture(vec![capture::json("seq", "seq")])
+            .expect(("seq", Value::I32(1)))
+            .build())
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter/SayHello")
+            .metadata(("authorization", Value::from("Bearer t0k")))
+            .message(Value::object([("name", "epoch")]))
+            .capture(vec![capture::json("seq", "epoch_seq")])
+            .expect(("epoch_seq", Value::I64(1_700_000_000_123)))
+            .build())
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter/SayHello")
+            .metadata(("authorization", Value::from("Bearer t0k")))
+            .message(Value::object([("name", "missing")]))
+            .expect_code(5)
+            .build())
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter/SayHello")
+            .message(Value::object([("name", "anole")]))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, true, true, false]);
+}
+
+#[tokio::test]
+async fn test_grpc_server_streaming() {
+    let addr = serve().await;
+    let url = format!("http://{}", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter/StreamHello")
+            .message(Value::object([("name", Value::from("anole")), ("times", Value::I32(3))]))
+            .capture(vec![capture::json("seq", "last_seq")])
+            .count("message_count")
+            .expect(("message_count", Value::U64(3)))
+            .build())
+        .with_grpc(GrpcTaskBuilder::new()
+            .url(&url)
+            .method("test.v1.Greeter/StreamHello")
+            .message(Value::object([("name", Value::from("anole")), ("times", Value::I32(5))]))
+            .capture(vec![capture::json("meta.trace_id", "trace_id")])
+            .max_messages(2)
+            .expect(("trace_id", Value::from("trace-2")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, Method}, value::{Part, Value}, capture};
+
+#[tokio::test]
+async fn test_multipart() {
+    let addr = common::serve(|req| {
+        if req.request_line().starts_with("GET /user") {
+            return common::json(r#"{"id": 42}"#);
+        }
+        let ct = req.header("content-type").unwrap_or_default();
+        let body = req.body_str();
+        let ok = ct.starts_with("multipart/form-data; boundary=")
+            && body.contains("name=\"user_id\"\r\n\r\n42\r\n")
+            && body.contains("name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\nPNG\r\n")
+            && body.contains("name=\"manifest\"; filename=\"Cargo.toml\"\r\nContent-Type: text/x-toml\r\n");
+        common::json(&format!(r#"{{"ok": {}}}"#, ok))
+    });
+    let user_url = format!("http://{}/user", addr);
+    let upload_url = format!("http://{}/upload", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&user_url)
+            .capture(vec![capture::json("id", "user_id")])
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&upload_url)
+            .method(Method::Post)
+            .multipart(("user_id", Part::text(":user_id")))
+            .multipart(("avatar", Part::bytes("PNG").file_name("a.png").mime("image/png")))
+            .multipart(("manifest", Part::file("Cargo.toml")))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use anole::{engine::Engine, task::db::{mysql::MysqlTask, DBTask, DBClientOption}, capture::{self, CapValueType}, value::Value};
+
+/// Runs against `ANOLE_TEST_MYSQL_URL`, e.g. `mysql://root@127.0.0.1:3306/test`, ignored by default, run with `cargo test -- --ignored`.
+fn mysql_url() -> String {
+    std::env::var("ANOLE_TEST_MYSQL_URL").expect("ANOLE_TEST_MYSQL_URL not set")
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_MYSQL_URL"]
+async fn test_mysql_types() {
+    let url = mysql_url();
+    let check = |key, expect| DBTask::new("SELECT 1").expect((key, expect));
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_mysql(MysqlTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT CAST(42 AS SIGNED) AS total, CAST(7 AS UNSIGNED) AS qty, 'anole' AS name, \
+                CAST('2022-03-04' AS DATE) AS day, CAST('05:06:07' AS TIME) AS at, CAST(12.50 AS DECIMAL(10, 2)) AS price, \
+                CAST('2022-03-04 05:06:07' AS DATETIME) AS created_at, CAST('{\"name\": \"anole\", \"roles\": [\"admin\"]}' AS JSON) AS profile, \
+                CAST('{\"a\": null}' AS JSON) AS nullable, X'CAFE' AS image, CAST(2.5 AS DOUBLE) AS weight")
+                .capture(vec![
+                    capture::column("total", "total", CapValueType::I64),
+                    capture::column("qty", "qty", CapValueType::U64),
+                    capture::column("name", "name", CapValueType::Str),
+                    capture::column("day", "day", CapValueType::Date),
+                    capture::column("at", "at", CapValueType::Time),
+                    capture::column("price", "price", CapValueType::Decimal),
+                    capture::column("created_at", "created_at", CapValueType::Timestamp),
+                    capture::column("profile", "profile", CapValueType::Json),
+                    capture::column("nullable", "nullable", CapValueType::Json),
+                    capture::column("image", "image", CapValueType::Bytes),
+                    capture::column_auto("weight", "weight"),
+                ]))
+            .with_task(check("total", Value::I64(42)))
+            .with_task(check("qty", Value::U64(7)))
+            .with_task(check("name", Value::from("anole")))
+            .with_task(check("price", Value::from("12.5")))
+            .with_task(check("created_at", Value::from("2022-03-04T05:06:07Z")))
+            .with_task(check("profile", Value::object([("name", Value::from("anole")), ("roles", Value::array(["admin"]))])))
+            .with_task(check("nullable", Value::object([("a", Value::Null)])))
+            .with_task(check("image", Value::Bytes(vec![0xca, 0xfe])))
+            .with_task(check("weight", Value::F64(2.5))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 10);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_MYSQL_URL"]
+async fn test_mysql_typed_binds() {
+    let url = mysql_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_mysql(MysqlTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT CAST(42 AS SIGNED) AS total, CAST('2022-03-04' AS DATE) AS day, CAST('05:06:07' AS TIME) AS at, \
+                CAST(12.50 AS DECIMAL(10, 2)) AS price, CAST('2022-03-04 05:06:07' AS DATETIME) AS created_at, X'CAFE' AS image, \
+                'it''s :name' AS quoted")
+                .capture(vec![
+                    capture::column("total", "total", CapValueType::I64),
+                    capture::column("day", "day", CapValueType::Date),
+                    capture::column("at", "at", CapValueType::Time),
+                    capture::column("price", "price", CapValueType::Decimal),
+                    capture::column("created_at", "created_at", CapValueType::Timestamp),
+                    capture::column("image", "image", CapValueType::Bytes),
+                    capture::column("quoted", "quoted", CapValueType::Str),
+                ])
+                .expect(("quoted", Value::from("it's :name"))))
+            .with_task(DBTask::new("SELECT :total + 1 = 43 AND :day = CAST('2022-03-04' AS DATE) AND :at = CAST('05:06:07' AS TIME) \
+                AND :price = 12.5 AND :created_at = CAST('2022-03-04 05:06:07' AS DATETIME) AND :image = X'CAFE' AS same")
+                .param("total")
+                .param("day")
+                .param("at")
+                .param("price")
+                .param("created_at")
+                .param("image")
+                .capture(vec![capture::column("same", "same", CapValueType::Bool)])
+                .expect(("same", Value::Bool(true)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_MYSQL_URL"]
+async fn test_mysql_dml() {
+    let url = mysql_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_mysql(MysqlTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("CREATE TEMPORARY TABLE orders (id INT AUTO_INCREMENT PRIMARY KEY, status VARCHAR(16) NOT NULL)"))
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('new'), ('paid')")
+                .rows_affected("inserted")
+                .expect(("inserted", Value::U64(2))))
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('refund')")
+                .last_insert_id("order_id")
+                .expect(("order_id", Value::I64(3))))
+            .with_task(DBTask::new("UPDATE orders SET status = 'shipped' WHERE id = :order_id")
+                .param("order_id")
+                .rows_affected("updated")
+                .expect(("updated", Value::U64(1))))
+            .with_task(DBTask::new("SELECT status FROM orders WHERE id = :order_id")
+                .param("order_id")
+                .capture(vec![capture::column("status", "status", CapValueType::Str)])
+                .expect(("status", Value::from("shipped")))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 5);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
@@ -0,0 +1,265 @@
+mod common;
+
+use anole::{engine::Engine, task::db::{postgres::PostgresTask, script::SqlScript, DBTask, DBClientOption}, capture::{self, CapValueType}, value::Value};
+
+/// Runs against `ANOLE_TEST_POSTGRES_URL`, e.g. `postgres://postgres@127.0.0.1:5432/postgres`, ignored by default, run with `cargo test -- --ignored`.
+fn postgres_url() -> String {
+    std::env::var("ANOLE_TEST_POSTGRES_URL").expect("ANOLE_TEST_POSTGRES_URL not set")
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_types() {
+    let url = postgres_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid AS id, \
+                '{\"name\": \"anole\", \"roles\": [\"admin\"]}'::jsonb AS profile, \
+                '2022-03-04 05:06:07+08'::timestamptz AS created_at, \
+                12.50::numeric(10, 2) AS price, \
+                ARRAY[1, 2, 3] AS nums, ARRAY['a', 'b'] AS tags, \
+                42::int8 AS total, 'anole'::text AS name")
+                .capture(vec![
+                    capture::column("id", "id", CapValueType::Uuid),
+                    capture::column("profile", "profile", CapValueType::Json),
+                    capture::column("created_at", "created_at", CapValueType::Timestamp),
+                    capture::column("price", "price", CapValueType::Decimal),
+                    capture::column("nums", "nums", CapValueType::array(CapValueType::I32)),
+                    capture::column("tags", "tags", CapValueType::array(CapValueType::Str)),
+                    capture::column("total", "total", CapValueType::I64),
+                    capture::column("name", "name", CapValueType::Str),
+                ])
+                .expect(("created_at", Value::from("2022-03-03T21:06:07Z"))))
+            .with_task(DBTask::new("SELECT ':id'::uuid = 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid AS same")
+                .param("id")
+                .capture(vec![capture::column("same", "same", CapValueType::Bool)])
+                .expect(("same", Value::Bool(true)))))
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT 1 AS n")
+                .capture(vec![capture::column("n", "n", CapValueType::I32)])
+                .expect(("price", Value::from("12.5")))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_column_auto() {
+    let url = postgres_url();
+    let check = |key, expect| DBTask::new("SELECT 1").expect((key, expect));
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT 7::int2 AS small, 42 AS n, 42::int8 AS total, 12.50::numeric(10, 2) AS price, \
+                '2022-03-04 05:06:07'::timestamp AS created_at, ARRAY['a', 'b'] AS tags, NULL::text AS nickname")
+                .capture(vec![
+                    capture::column_auto("small", "small"),
+                    capture::column_auto("n", "n"),
+                    capture::column_auto("total", "total"),
+                    capture::column_auto("price", "price"),
+                    capture::column_auto("created_at", "created_at"),
+                    capture::column_auto("tags", "tags"),
+                    capture::column_auto("nickname", "nickname"),
+                ]))
+            .with_task(check("small", Value::I32(7)))
+            .with_task(check("n", Value::I32(42)))
+            .with_task(check("total", Value::I64(42)))
+            .with_task(check("price", Value::from("12.5")))
+            .with_task(check("created_at", Value::from("2022-03-04T05:06:07Z")))
+            .with_task(check("tags", Value::array(vec![Value::from("a"), Value::from("b")])))
+            .with_task(check("nickname", Value::Null))
+            .with_task(DBTask::new("SELECT 42 AS n")
+                .capture(vec![capture::column("n", "mismatch", CapValueType::I64)]))
+            .with_task(DBTask::new("SELECT point(1, 2) AS p")
+                .capture(vec![capture::column_auto("p", "p")])))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    let results = items.iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(results, vec![true, true, true, true, true, true, true, true, false, false], "{:?}", items);
+    assert!(items[8].description.contains("mismatched types"), "{}", items[8].description);
+    assert!(items[9].description.contains("unsupport column type POINT"), "{}", items[9].description);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_extended_types() {
+    let url = postgres_url();
+    let check = |key, expect| DBTask::new("SELECT 1").expect((key, expect));
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT 1.1::float4 AS ratio, 2.5::float8 AS weight, '\\xcafe'::bytea AS image, \
+                '2022-03-04 05:06:07.25+08'::timestamptz AS updated_at, 12.50::numeric(10, 2) AS price, ARRAY[1.5, 2]::float8[] AS weights")
+                .capture(vec![
+                    capture::column_auto("ratio", "ratio"),
+                    capture::column("weight", "weight", CapValueType::F64),
+                    capture::column_auto("image", "image"),
+                    capture::column_auto("updated_at", "updated_at"),
+                    capture::column_auto("price", "price"),
+                    capture::column_auto("weights", "weights"),
+                ]))
+            .with_task(check("ratio", Value::F64(1.1)))
+            .with_task(check("weight", Value::F64(2.5)))
+            .with_task(check("image", Value::Bytes(vec![0xca, 0xfe])))
+            .with_task(check("updated_at", Value::from("2022-03-03T21:06:07.25Z")))
+            .with_task(check("price", Value::from("12.5")))
+            .with_task(check("weights", Value::array(vec![Value::F64(1.5), Value::F64(2.0)])))
+            .with_task(DBTask::new("SELECT :image = '\\xcafe'::bytea AND :updated_at = '2022-03-03 21:06:07.25Z'::timestamptz \
+                AND :price = 12.5 AND :weight = 2.5::float8 AS same")
+                .param("image")
+                .param("updated_at")
+                .param("price")
+                .param("weight")
+                .capture(vec![capture::column("same", "same", CapValueType::Bool)])
+                .expect(("same", Value::Bool(true)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 8);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_captured_values() {
+    let url = postgres_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT ARRAY['a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid] AS ids, \
+                '[1, {\"a\": true}]'::json AS doc, 41::int8 AS seq")
+                .capture(vec![
+                    capture::column("seq", "seq", CapValueType::I64),
+                    capture::column("ids", "ids", CapValueType::array(CapValueType::Uuid)),
+                    capture::column("doc", "doc", CapValueType::Json),
+                ])
+                .expect(("doc", Value::array([Value::I32(1), Value::object([("a", true)])]))))
+            .with_task(DBTask::new("SELECT (:doc::jsonb -> 1 ->> 'a')::bool AS a, :seq + 1 AS next")
+                .param("doc")
+                .param("seq")
+                .capture(vec![
+                    capture::column("a", "a", CapValueType::Bool),
+                    capture::column("next", "next", CapValueType::I64),
+                ])
+                .expect(("a", Value::Bool(true))))
+            .with_task(DBTask::new("SELECT '{\"a\": null}'::jsonb AS nullable, 'null'::jsonb AS empty")
+                .capture(vec![
+                    capture::column("nullable", "nullable", CapValueType::Json),
+                    capture::column("empty", "empty", CapValueType::Json),
+                ])
+                .expect(("nullable", Value::object([("a", Value::Null)]))))
+            .with_task(DBTask::new("SELECT 1").expect(("empty", Value::Null))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_dml() {
+    let url = postgres_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("CREATE TEMPORARY TABLE orders (id serial PRIMARY KEY, status text NOT NULL)"))
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('new'), ('paid')")
+                .rows_affected("inserted")
+                .expect(("inserted", Value::U64(2))))
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('refund') RETURNING id")
+                .capture(vec![capture::column("id", "order_id", CapValueType::I32)])
+                .expect(("order_id", Value::I32(3))))
+            .with_task(DBTask::new("UPDATE orders SET status = 'shipped' WHERE id = :order_id")
+                .param("order_id")
+                .rows_affected("updated")
+                .expect(("updated", Value::U64(1)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+
+    // unsupported last_insert_id fails the task only, later tasks still run
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url).max_connections(1))
+            .with_task(DBTask::new("SELECT 1").last_insert_id("id"))
+            .with_task(DBTask::new("SELECT 1")))
+        .run().await.unwrap();
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![false, true]);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_rollback() {
+    let url = postgres_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .rollback()
+            .with_task(DBTask::new("CREATE TABLE anole_rollback (id serial PRIMARY KEY, name text)"))
+            .with_task(DBTask::new("INSERT INTO anole_rollback (name) VALUES ('anole')")
+                .rows_affected("inserted")
+                .expect(("inserted", Value::U64(1)))))
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("SELECT to_regclass('anole_rollback') IS NULL AS missing")
+                .capture(vec![capture::column("missing", "missing", CapValueType::Bool)])
+                .expect(("missing", Value::Bool(true)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_POSTGRES_URL"]
+async fn test_postgres_script() {
+    let url = postgres_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_setup(SqlScript::sql("CREATE TABLE IF NOT EXISTS anole_script (id serial PRIMARY KEY, name text, active bool); \
+            INSERT INTO anole_script (name, active) VALUES ('anole', true), ('O''Brien', false);")
+            .options(DBClientOption::builder().url(&url)))
+        .with_postgres(PostgresTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("SELECT name, active FROM anole_script WHERE name LIKE 'O%'")
+                .capture(vec![
+                    capture::column("name", "name", CapValueType::Str),
+                    capture::column("active", "active", CapValueType::Bool),
+                ])
+                .expect(("name", Value::from("O'Brien")))))
+        .with_teardown(SqlScript::sql("DELETE FROM anole_script WHERE name = :name AND active = :active; \
+            DO $$ BEGIN IF (SELECT count(*) FROM anole_script) <> 1 THEN RAISE EXCEPTION 'not deleted'; END IF; END $$; \
+            DROP TABLE anole_script;")
+            .options(DBClientOption::builder().url(&url)))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 1);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
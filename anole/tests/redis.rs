@@ -0,0 +1,66 @@
+mod common;
+
+use anole::{engine::Engine, task::redis::{RedisTask, RedisCommand}, capture, value::Value};
+
+/// Runs against `ANOLE_TEST_REDIS_URL`, e.g. `redis://127.0.0.1:6379/0`, ignored by default, run with `cargo test -- --ignored`.
+fn redis_url() -> String {
+    std::env::var("ANOLE_TEST_REDIS_URL").expect("ANOLE_TEST_REDIS_URL not set")
+}
+
+#[tokio::test]
+#[ignore = "needs ANOLE_TEST_REDIS_URL"]
+async fn test_redis_commands() {
+    let url = redis_url();
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_redis(RedisTask::default()
+            .url(&url)
+            .with_command(RedisCommand::cmd("DEL").arg("anole:user:42").arg("anole:hash:42").arg("anole:list:42"))
+            .with_command(RedisCommand::set("anole:user:42", r#"{"name": "anole", "roles": ["admin"]}"#).save("set").expect(("set", Value::from("OK"))))
+            .with_command(RedisCommand::cmd("SET").arg("anole:user_id").arg(42))
+            .with_command(RedisCommand::get("anole:user_id").save("user_id").expect(("user_id", Value::from("42"))))
+            // `anole:user::user_id` is rendered as `anole:user:42`
+            .with_command(RedisCommand::get("anole:user::user_id")
+                .capture(vec![capture::json("name", "name"), capture::json("roles|0", "role")])
+                .expect(("role", Value::from("admin"))))
+            .with_command(RedisCommand::exists("anole:user::user_id").save("exists").expect(("exists", Value::I64(1))))
+            .with_command(RedisCommand::ttl("anole:user::user_id").save("ttl").expect(("ttl", Value::I64(-1))))
+            .with_command(RedisCommand::cmd("EXPIRE").arg("anole:user::user_id").arg(60))
+            .with_command(RedisCommand::cmd("HSET").arg("anole:hash::user_id").arg("name").arg(":name").arg("age").arg(3))
+            .with_command(RedisCommand::hgetall("anole:hash::user_id").save("hash").capture(vec![capture::json("age", "age")])
+                .expect(("hash", Value::object([("age", "3"), ("name", "anole")]))))
+            .with_command(RedisCommand::cmd("RPUSH").arg("anole:list::user_id").arg("a").arg("b").arg("c"))
+            .with_command(RedisCommand::lrange("anole:list::user_id", 0, -1).save("list").expect(("list", Value::array(["a", "b", "c"]))))
+            .with_command(RedisCommand::del("anole:user::user_id").save("deleted").expect(("deleted", Value::I64(1))))
+            .with_command(RedisCommand::get("anole:user::user_id").save("missing").expect(("missing", Value::Null)))
+            // WRONGTYPE error reply fails the command
+            .with_command(RedisCommand::get("anole:hash::user_id"))
+            // saved `hash` and `list` don't replace the `:hash` and `:list` segments, which don't follow a delimiter
+            .with_command(RedisCommand::cmd("DEL").arg("anole:user_id").arg("anole:hash::user_id").arg("anole:list::user_id")))
+        .with_redis(RedisTask::default()
+            .url(&url)
+            .with_command(RedisCommand::ttl("anole:user:42").save("ttl_after").expect(("ttl_after", Value::I64(-2)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 17);
+    let failed = items.iter().filter(|r| !r.is_success()).collect::<Vec<_>>();
+    assert_eq!(failed.len(), 1, "{:?}", items);
+    assert!(format!("{:?}", failed[0]).contains("GET anole:hash:42"), "{:?}", failed);
+}
+
+#[tokio::test]
+async fn test_redis_unreachable() {
+    let r = Engine::new()
+        .with_redis(RedisTask::default()
+            .url("redis://127.0.0.1:1/0")
+            .with_command(RedisCommand::get("anole")))
+        .run().await;
+    assert!(r.is_err());
+
+    let r = Engine::new()
+        .with_redis(RedisTask::default().with_command(RedisCommand::get("anole")))
+        .run().await;
+    assert!(r.is_err());
+}
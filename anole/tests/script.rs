@@ -0,0 +1,91 @@
+mod common;
+
+use anole::{engine::Engine, task::db::{sqlite::SqliteTask, script::SqlScript, DBTask, DBClientOption}, capture::{self, CapValueType}, value::Value};
+
+fn count(sql: &str, expect: i32) -> DBTask<'_> {
+    DBTask::new(sql)
+        .capture(vec![capture::column("total", "total", CapValueType::I32)])
+        .expect(("total", Value::I32(expect)))
+}
+
+#[tokio::test]
+async fn test_setup_teardown() {
+    let path = std::env::temp_dir().join(format!("anole-script-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.to_str().unwrap());
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_setup(SqlScript::file("tests/fixtures/seed.sql")
+            .options(DBClientOption::builder().url(&url)))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("SELECT id, name FROM users WHERE name LIKE 'O%'")
+                .capture(vec![
+                    capture::column("id", "user_id", CapValueType::I64),
+                    capture::column("name", "user_name", CapValueType::Str),
+                ])
+                .expect(("user_name", Value::from("O'Brien"))))
+            .with_task(count("SELECT count(*) AS total FROM orders WHERE note = 'time 12:30:00'", 1)))
+        .with_teardown(SqlScript::sql("DELETE FROM orders WHERE user_id = :user_id; \
+            DELETE FROM users WHERE name = :user_name AND id = ':user_id'; -- :missing is kept in comments")
+            .options(DBClientOption::builder().url(&url)))
+        .run().await.unwrap();
+
+    let (reporter, recv_after) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(count("SELECT count(*) AS total FROM users", 1))
+            .with_task(count("SELECT count(*) AS total FROM orders", 1)))
+        .run().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let items = recv.try_iter().chain(recv_after.try_iter()).collect::<Vec<_>>();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_teardown_after_failure() {
+    let path = std::env::temp_dir().join(format!("anole-script-failure-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.to_str().unwrap());
+    let r = Engine::new()
+        .with_setup(SqlScript::file("tests/fixtures/seed.sql")
+            .options(DBClientOption::builder().url(&url)))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("SELECT * FROM missing_table")))
+        .with_teardown(SqlScript::sql("DELETE FROM orders; DELETE FROM users;")
+            .options(DBClientOption::builder().url(&url)))
+        .run().await;
+    assert!(r.is_err());
+
+    let r = Engine::new()
+        .with_setup(SqlScript::file("tests/fixtures/missing.sql")
+            .options(DBClientOption::builder().url(&url)))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("INSERT INTO users (name) VALUES ('leaked')")))
+        .with_teardown(SqlScript::sql("INSERT INTO users (name) VALUES ('teardown')")
+            .options(DBClientOption::builder().url(&url)))
+        .run().await;
+    assert!(r.is_err());
+
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(count("SELECT count(*) AS total FROM orders", 0))
+            .with_task(count("SELECT count(*) AS total FROM users WHERE name = 'teardown'", 1))
+            .with_task(count("SELECT count(*) AS total FROM users WHERE name = 'leaked'", 0)))
+        .run().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
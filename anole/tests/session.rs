@@ -0,0 +1,122 @@
+mod common;
+
+use std::net::SocketAddr;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, session::{HttpSessionOption, RedirectPolicy}}, capture, value::Value};
+
+fn login_server() -> SocketAddr {
+    common::serve(|req| {
+        let line = req.request_line();
+        if let Some(user) = line.strip_prefix("GET /login/").and_then(|l| l.split(' ').next()) {
+            let cookie = format!("sid={}; Path=/", user);
+            return common::response("200 OK", &[("set-cookie", &cookie)], "");
+        }
+        if let Some(user) = line.strip_prefix("GET /redirect-login/").and_then(|l| l.split(' ').next()) {
+            let cookie = format!("sid={}; Path=/", user);
+            return common::response("302 Found", &[("set-cookie", &cookie), ("location", "/me")], "");
+        }
+        let sid = req.header("cookie")
+            .and_then(|c| c.strip_prefix("sid=").map(|c| c.to_string()))
+            .unwrap_or_default();
+        common::json(&format!(r#"{{"user": "{}"}}"#, sid))
+    })
+}
+
+#[tokio::test]
+async fn test_shared_cookie() {
+    let addr = login_server();
+    let login = format!("http://{}/login/alice", addr);
+    let me = format!("http://{}/me", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new().url(&login).build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .capture(vec![capture::json("user", "user")])
+            .expect(("user", Value::from("alice")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .verbose(true)
+            .capture(vec![capture::json("user", "verbose_user")])
+            .expect(("verbose_user", Value::from("alice")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .share_session(false)
+            .capture(vec![capture::json("user", "user")])
+            .expect(("user", Value::from("")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_named_sessions() {
+    let addr = login_server();
+    let login_alice = format!("http://{}/login/alice", addr);
+    let login_bob = format!("http://{}/login/bob", addr);
+    let me = format!("http://{}/me", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http_session("alice", HttpSessionOption::builder())
+        .with_http_session("bob", HttpSessionOption::builder())
+        .with_http(HttpTaskBuilder::new().url(&login_alice).session("alice").build())
+        .with_http(HttpTaskBuilder::new().url(&login_bob).session("bob").build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .session("alice")
+            .capture(vec![capture::json("user", "alice")])
+            .expect(("alice", Value::from("alice")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .session("bob")
+            .capture(vec![capture::json("user", "bob")])
+            .expect(("bob", Value::from("bob")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_override_client_shares_cookie() {
+    let addr = login_server();
+    let login = format!("http://{}/redirect-login/carol", addr);
+    let me = format!("http://{}/me", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        // the 302 is captured, its cookie is stored into the session
+        .with_http(HttpTaskBuilder::new()
+            .url(&login)
+            .redirect(RedirectPolicy::None)
+            .expect_status(302)
+            .capture(vec![capture::header("location", "location")])
+            .expect(("location", Value::from("/me")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .redirect(RedirectPolicy::None)
+            .capture(vec![capture::json("user", "override_user")])
+            .expect(("override_user", Value::from("carol")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&me)
+            .capture(vec![capture::json("user", "user")])
+            .expect(("user", Value::from("carol")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
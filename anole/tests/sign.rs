@@ -0,0 +1,85 @@
+mod common;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, Method, sign::{CanonicalRequest, HmacSigner, SigV4Signer, Signer}}, capture, value::Value};
+
+fn canonical(method: &str, path: &str, query: Vec<(&str, &str)>, body_hash: &str, timestamp: i64, nonce: &str) -> CanonicalRequest {
+    CanonicalRequest {
+        method: method.to_string(),
+        host: "example.amazonaws.com".to_string(),
+        path: path.to_string(),
+        query: query.into_iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        headers: vec![],
+        body_hash: body_hash.to_string(),
+        timestamp,
+        nonce: nonce.to_string(),
+    }
+}
+
+fn store() -> anole::store::Store {
+    anole::store::Store::default()
+}
+
+#[test]
+fn test_sigv4_vanilla() {
+    // aws4 test suite: get-vanilla
+    let req = canonical("GET", "/", vec![], "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855", 1440938160, "");
+    let signer = SigV4Signer::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "us-east-1", "service");
+    let s = signer.sign(&req, &store()).unwrap();
+    assert!(s.headers.contains(&("x-amz-date".to_string(), "20150830T123600Z".to_string())));
+    assert!(s.headers.contains(&("authorization".to_string(),
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31".to_string())));
+}
+
+#[test]
+fn test_hmac() {
+    let req = canonical("POST", "/v1/orders", vec![("a", "1"), ("b", "x y")],
+        "1b1f97bc2a5eae44ac6ff39b1bd3a2a1b4b4def20f1dd47b9f4e4ac1b15a2ba6", 1700000000, "n1");
+    assert_eq!(HmacSigner::string_to_sign(&req),
+        "POST\n/v1/orders\na=1&b=x%20y\n1b1f97bc2a5eae44ac6ff39b1bd3a2a1b4b4def20f1dd47b9f4e4ac1b15a2ba6\n1700000000\nn1");
+    let s = HmacSigner::new("secret").sign(&req, &store()).unwrap();
+    assert!(s.headers.contains(&("x-timestamp".to_string(), "1700000000".to_string())));
+    assert!(s.headers.contains(&("x-nonce".to_string(), "n1".to_string())));
+    assert!(s.headers.iter().any(|(k, v)| k == "x-signature" && v.len() == 64));
+}
+
+#[tokio::test]
+async fn test_hmac_request() {
+    let addr = common::serve(|req| {
+        let line = req.request_line().split(' ').collect::<Vec<_>>();
+        let url = url::Url::parse(&format!("http://localhost{}", line[1])).unwrap();
+        let mut query = url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>();
+        query.sort();
+        let expect = CanonicalRequest {
+            method: line[0].to_string(),
+            host: String::new(),
+            path: url.path().to_string(),
+            query,
+            headers: vec![],
+            body_hash: hex::encode(<sha2::Sha256 as sha2::Digest>::digest(&req.body)),
+            timestamp: req.header("x-timestamp").unwrap().parse().unwrap(),
+            nonce: req.header("x-nonce").unwrap(),
+        };
+        let s = HmacSigner::new("secret").sign(&expect, &anole::store::Store::default()).unwrap();
+        let signature = s.headers.iter().find(|(k, _)| k == "x-signature").map(|(_, v)| v.to_owned());
+        let ok = signature == req.header("x-signature") && req.header("x-app-id").as_deref() == Some("anole");
+        common::json(&format!(r#"{{"ok": {}}}"#, ok))
+    });
+    let url = format!("http://{}/v1/orders?b=2&a=1", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .method(Method::Post)
+            .query(("c", Value::from("x y")))
+            .json(Value::object([("id", 1)]))
+            .signer(HmacSigner::new("secret").key_id("x-app-id", "anole"))
+            .capture(vec![capture::json("ok", "ok")])
+            .expect(("ok", Value::Bool(true)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 1);
+    assert!(items[0].is_success(), "{:?}", items);
+}
@@ -0,0 +1,391 @@
+mod common;
+
+use anole::{engine::Engine, task::db::{sqlite::SqliteTask, script::SqlScript, DatabaseTask, DBTask, DBClientOption}, capture::{self, CapValueType}, value::Value};
+use sqlx::types::time;
+
+#[tokio::test]
+async fn test_sqlite_memory() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, active BOOLEAN, \
+                born TEXT, created_at TEXT, profile TEXT)"))
+            .with_task(DBTask::new("INSERT INTO users (name, active, born, created_at, profile) VALUES \
+                ('anole', 1, '2022-03-04', '2022-03-04 05:06:07', '{\"roles\": [\"admin\"]}'), \
+                ('gecko', 0, '2021-01-02', '2021-01-02 03:04:05', '{}')"))
+            .with_task(DBTask::new("SELECT id, name, active, born, created_at, profile FROM users WHERE name = 'anole'")
+                .capture(vec![
+                    capture::column("id", "id", CapValueType::I64),
+                    capture::column("name", "name", CapValueType::Str),
+                    capture::column("active", "active", CapValueType::Bool),
+                    capture::column("born", "born", CapValueType::Date),
+                    capture::column("created_at", "created_at", CapValueType::Timestamp),
+                    capture::column("profile", "profile", CapValueType::Json),
+                ])
+                .expect(("created_at", Value::from("2022-03-04T05:06:07Z"))))
+            .with_task(DBTask::new("SELECT count(*) AS total FROM users WHERE id > ':id'")
+                .param("id")
+                .capture(vec![capture::column("total", "total", CapValueType::I32)])
+                .expect(("total", Value::I32(1))))
+            .with_task(DBTask::new("SELECT name FROM users ORDER BY id")
+                .capture(vec![capture::column("name", "names", CapValueType::Str)])
+                .expect(("names|1", Value::from("gecko")))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 5);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_sqlite_json_null() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("SELECT '{\"a\": null, \"b\": [null]}' AS profile, 'null' AS empty")
+                .capture(vec![
+                    capture::column("profile", "profile", CapValueType::Json),
+                    capture::column("empty", "empty", CapValueType::Json),
+                ])
+                .expect(("profile", Value::object([("a", Value::Null), ("b", Value::array([Value::Null]))]))))
+            .with_task(check("empty", Value::Null))
+            // bound back as JSON text keeping the null
+            .with_task(DBTask::new("SELECT json_type(:profile, '$.a') AS a_type")
+                .param("profile")
+                .capture(vec![capture::column("a_type", "a_type", CapValueType::Str)])
+                .expect(("a_type", Value::from("null")))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_sqlite_file() {
+    let path = std::env::temp_dir().join(format!("anole-sqlite-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.to_str().unwrap());
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("CREATE TABLE orders (id INTEGER PRIMARY KEY, amount INTEGER)"))
+            .with_task(DBTask::new("INSERT INTO orders (amount) VALUES (12), (30)")))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("SELECT sum(amount) AS total FROM orders")
+                .capture(vec![capture::column("total", "total", CapValueType::I64)])
+                .expect(("total", Value::I64(42)))))
+        .run().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+
+    let missing = std::env::temp_dir().join(format!("anole-sqlite-missing-{}.db", std::process::id()));
+    let url = format!("sqlite://{}", missing.to_str().unwrap());
+    let r = Engine::new()
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("SELECT 1")))
+        .run().await;
+    assert!(r.is_err());
+}
+
+#[tokio::test]
+async fn test_sqlite_bind() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, born TEXT)"))
+            .with_task(DBTask::new("INSERT INTO users (name, born) VALUES ('O''Brien', '2022-03-04'), ('anole', '2021-01-02')"))
+            .with_task(DBTask::new("SELECT 'O''Brien' AS name, 'x'' OR ''1''=''1' AS evil, date('2022-03-04') AS born, 2 AS id")
+                .capture(vec![
+                    capture::column("name", "name", CapValueType::Str),
+                    capture::column("evil", "evil", CapValueType::Str),
+                    capture::column("born", "born", CapValueType::Date),
+                    capture::column("id", "id", CapValueType::I64),
+                ]))
+            .with_task(DBTask::new("SELECT count(*) AS total FROM users WHERE name = :name AND born = :born")
+                .param("name")
+                .param("born")
+                .capture(vec![capture::column("total", "by_name", CapValueType::I32)])
+                .expect(("by_name", Value::I32(1))))
+            .with_task(DBTask::new("SELECT count(*) AS total FROM users WHERE name = ':evil'")
+                .param("evil")
+                .capture(vec![capture::column("total", "by_evil", CapValueType::I32)])
+                .expect(("by_evil", Value::I32(0))))
+            .with_task(DBTask::new("SELECT name, ':id' AS literal FROM users WHERE id = :id AND ':kept' = ':kept'")
+                .param("id")
+                .capture(vec![capture::column("name", "by_id", CapValueType::Str)])
+                .expect(("by_id", Value::from("anole"))))
+            .with_task(DBTask::new("SELECT name FROM users WHERE id = :missing")
+                .param("missing")))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, true, true, true, true, false]);
+}
+
+#[tokio::test]
+async fn test_sqlite_dml() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("CREATE TABLE orders (id INTEGER PRIMARY KEY, status TEXT NOT NULL)"))
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('new'), ('new')")
+                .rows_affected("inserted")
+                .expect(("inserted", Value::U64(2))))
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('paid')")
+                .last_insert_id("order_id")
+                .expect(("order_id", Value::I64(3))))
+            .with_task(DBTask::new("UPDATE orders SET status = 'shipped' WHERE id = :order_id OR status = 'new'")
+                .param("order_id")
+                .rows_affected("updated")
+                .expect(("updated", Value::U64(3))))
+            .with_task(DBTask::new("DELETE FROM orders WHERE id = :order_id")
+                .param("order_id")
+                .rows_affected("deleted")
+                .expect(("deleted", Value::U64(1))))
+            .with_task(DBTask::new("DELETE FROM orders WHERE status = 'new'")
+                .rows_affected("deleted")
+                .expect(("deleted", Value::U64(1)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, true, true, true, false]);
+}
+
+#[tokio::test]
+async fn test_sqlite_transaction() {
+    let path = std::env::temp_dir().join(format!("anole-sqlite-tx-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = format!("sqlite://{}?mode=rwc", path.to_str().unwrap());
+    let count = |expect: i32| DBTask::new("SELECT count(*) AS total FROM orders")
+        .capture(vec![capture::column("total", "total", CapValueType::I32)])
+        .expect(("total", Value::I32(expect)));
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(DBTask::new("CREATE TABLE orders (id INTEGER PRIMARY KEY, status TEXT NOT NULL)")))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .rollback()
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('new'), ('paid')"))
+            .with_task(count(2))
+            .with_task(DBTask::commit()))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(count(0))
+            .with_task(DBTask::begin())
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('new')"))
+            .with_task(DBTask::rollback())
+            .with_task(count(0))
+            .with_task(DBTask::begin())
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('paid')"))
+            .with_task(DBTask::commit())
+            .with_task(DBTask::commit())
+            .with_task(DBTask::begin())
+            .with_task(DBTask::begin())
+            .with_task(DBTask::new("INSERT INTO orders (status) VALUES ('refund')")))
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url(&url))
+            .with_task(count(1)))
+        .run().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![
+        true,
+        true, true, false,
+        true, true, true, true, true, true, true, true, false, true, false, true, false,
+        true,
+    ]);
+}
+
+fn check(key: &str, expect: Value) -> DBTask<'_> {
+    DBTask::new("SELECT 1").expect((key, expect))
+}
+
+#[tokio::test]
+async fn test_sqlite_column_auto() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, active BOOLEAN, \
+                born DATE, created_at DATETIME, nickname TEXT)"))
+            .with_task(DBTask::new("INSERT INTO users (name, active, born, created_at) VALUES ('anole', 1, '2022-03-04', '2022-03-04 05:06:07')"))
+            .with_task(DBTask::new("SELECT id, name, active, born, created_at, nickname, 2 + 3 AS total FROM users")
+                .capture(vec![
+                    capture::column_auto("id", "id"),
+                    capture::column_auto("name", "name"),
+                    capture::column_auto("active", "active"),
+                    capture::column_auto("born", "born"),
+                    capture::column_auto("created_at", "created_at"),
+                    capture::column_auto("nickname", "nickname"),
+                    capture::column_auto("total", "total"),
+                ])
+                .expect(("active", Value::Bool(true))))
+            .with_task(check("id", Value::I64(1)))
+            .with_task(check("name", Value::from("anole")))
+            .with_task(check("born", Value::Date(time::Date::try_from_ymd(2022, 3, 4).unwrap())))
+            .with_task(check("created_at", Value::from("2022-03-04T05:06:07Z")))
+            .with_task(check("nickname", Value::Null))
+            .with_task(check("total", Value::I64(5)))
+            .with_task(DBTask::new("SELECT name FROM users")
+                .capture(vec![capture::column("name", "mismatch", CapValueType::I64)]))
+            .with_task(DBTask::new("SELECT name FROM users")
+                .capture(vec![capture::column_auto("missing", "missing")]))
+            .with_task(DBTask::new("SELECT name AS born FROM users")
+                .capture(vec![capture::column("born", "bad_date", CapValueType::Date)])))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    let results = items.iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(results, vec![true, true, true, true, true, true, true, true, true, false, false, false], "{:?}", items);
+    assert!(items[9].description.starts_with("capture name failed: "), "{}", items[9].description);
+    assert!(items[10].description.contains("no column found for name: missing"), "{}", items[10].description);
+    assert!(items[11].description.contains("anole is not a date"), "{}", items[11].description);
+}
+
+#[tokio::test]
+async fn test_sqlite_table() {
+    let (reporter, recv) = common::reporter();
+    let row = |id: i64, name: &str, active: bool| Value::object([
+        ("id", Value::from(id)), ("name", Value::from(name)), ("active", Value::Bool(active))]);
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, active BOOLEAN)"))
+            .with_task(DBTask::new("INSERT INTO users (name, active) VALUES ('anole', 1), ('gecko', 0), ('anole', 0)"))
+            .with_task(DBTask::new("SELECT id, name, active FROM users ORDER BY id DESC")
+                .table("users")
+                .row_count("user_count")
+                .expect_rows(vec![row(1, "anole", true), row(2, "gecko", false), row(3, "anole", false)])
+                .expect_contains(Value::object([("name", "gecko")]))
+                .expect_column("id", |v| v.as_i64().is_ok_and(|id| id > 0))
+                .expect(("user_count", Value::U64(3))))
+            .with_task(check("users", Value::array(vec![row(3, "anole", false), row(2, "gecko", false), row(1, "anole", true)])))
+            .with_task(DBTask::new("SELECT name FROM users")
+                .expect_rows(vec![Value::object([("name", "anole")]), Value::object([("name", "gecko")]), Value::object([("name", "gecko")])]))
+            .with_task(DBTask::new("SELECT name FROM users")
+                .expect_rows(vec![Value::object([("name", "anole")]), Value::object([("name", "gecko")])]))
+            .with_task(DBTask::new("SELECT id, name FROM users")
+                .expect_contains(Value::object([("id", Value::from(2)), ("name", Value::from("anole"))])))
+            .with_task(DBTask::new("SELECT id, active FROM users")
+                .expect_column("active", |v| *v == Value::Bool(false)))
+            .with_task(DBTask::new("SELECT id FROM users WHERE id > 5")
+                .row_count("none")
+                .expect_rows(vec![])
+                .expect(("none", Value::U64(0)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    let results = items.iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(results, vec![true, true, true, true, false, false, false, false, true], "{:?}", items);
+    assert!(items[7].description.contains("column active of row 0 does not match"), "{}", items[7].description);
+}
+
+#[tokio::test]
+async fn test_sqlite_extended_types() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_sqlite(SqliteTask::default()
+            .options(DBClientOption::builder().url("sqlite::memory:"))
+            .with_task(DBTask::new("CREATE TABLE items (id INTEGER PRIMARY KEY, price NUMERIC, weight REAL, \
+                image BLOB, updated_at DATETIME, note TEXT)"))
+            .with_task(DBTask::new("INSERT INTO items (price, weight, image, updated_at) VALUES \
+                (12.50, 1.5, X'CAFE', '2022-03-04 05:06:07'), (3, 2, NULL, '2022-03-04T05:06:07.25Z')"))
+            .with_task(DBTask::new("SELECT price, weight, image, updated_at, note, '05:06:07' AS at FROM items WHERE id = 1")
+                .capture(vec![
+                    capture::column("at", "at", CapValueType::Time),
+                    capture::column("price", "price", CapValueType::Decimal),
+                    capture::column_auto("weight", "weight"),
+                    capture::column_auto("image", "image"),
+                    capture::column_auto("updated_at", "updated_at"),
+                    capture::column_auto("note", "note"),
+                ]))
+            .with_task(check("price", Value::from("12.5")))
+            .with_task(check("weight", Value::F64(1.5)))
+            .with_task(check("image", Value::Bytes(vec![0xca, 0xfe])))
+            .with_task(check("updated_at", Value::from("2022-03-04T13:06:07+08:00")))
+            .with_task(check("note", Value::Null))
+            .with_task(DBTask::new("SELECT price, weight, updated_at FROM items WHERE id = 2")
+                .capture(vec![
+                    capture::column("price", "int_price", CapValueType::Decimal),
+                    capture::column("weight", "int_weight", CapValueType::F64),
+                    capture::column("updated_at", "fraction", CapValueType::Timestamp),
+                ])
+                .expect(("fraction", Value::from("2022-03-04T05:06:07.25Z"))))
+            .with_task(check("int_price", Value::from("3")))
+            .with_task(check("int_weight", Value::F64(2.0)))
+            .with_task(DBTask::new("SELECT count(*) AS total FROM items WHERE image = :image AND updated_at = :updated_at AND price = :price \
+                AND time(updated_at) = :at")
+                .param("image")
+                .param("at")
+                .param("updated_at")
+                .param("price")
+                .capture(vec![capture::column("total", "total", CapValueType::I64)])
+                .expect(("total", Value::I64(1)))))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 12);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_sqlite_connection() {
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        // separate pools of `sqlite::memory:` open distinct databases, tables are visible only through the shared one.
+        .with_database_connection("app", DBClientOption::builder().url("sqlite::memory:"))
+        .with_setup(SqlScript::sql("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);").connection("app"))
+        .with_sqlite(SqliteTask::default()
+            .connection("app")
+            .with_task(DBTask::new("INSERT INTO users (name) VALUES ('anole'), ('gecko')")))
+        .with_database(DatabaseTask::default()
+            .connection("app")
+            .with_task(DBTask::new("SELECT count(*) AS total FROM users")
+                .capture(vec![capture::column("total", "total", CapValueType::I64)])
+                .expect(("total", Value::I64(2)))))
+        .with_teardown(SqlScript::sql("DELETE FROM users;").connection("app"))
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+
+    let r = Engine::new()
+        .with_sqlite(SqliteTask::default()
+            .connection("missing")
+            .with_task(DBTask::new("SELECT 1")))
+        .run().await;
+    assert!(r.is_err());
+
+    let r = Engine::new()
+        .with_database_connection("app", DBClientOption::builder().url("postgres://postgres@127.0.0.1:1/anole"))
+        .with_sqlite(SqliteTask::default()
+            .connection("app")
+            .with_task(DBTask::new("SELECT 1")))
+        .run().await;
+    assert!(r.is_err());
+}
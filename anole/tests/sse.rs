@@ -0,0 +1,102 @@
+mod common;
+
+use std::time::Duration;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, sse::Sse}, capture, value::Value};
+
+const EVENTS: &str = "event: started\ndata: {\"job_id\": 7}\n\n\
+: keep-alive\n\n\
+event: progress\ndata: {\"percent\": 50}\n\n\
+event: progress\r\ndata: {\"percent\": 100}\r\n\r\n\
+data: line1\ndata: line2\n\n\
+event: done\nid: 5\ndata: {\"result\": {\"code\": \"ok\", \"error\": null}}\n\n\
+event: progress\ndata: {\"percent\": 0}\n\n";
+
+fn event_stream(body: &str) -> String {
+    common::response("200 OK", &[("content-type", "text/event-stream")], body)
+}
+
+#[tokio::test]
+async fn test_sse_until_event() {
+    let addr = common::serve(|req| {
+        if req.header("accept").as_deref() != Some("text/event-stream") {
+            return common::response("406 Not Acceptable", &[], "");
+        }
+        event_stream(EVENTS)
+    });
+    let url = format!("http://{}/jobs/7/events", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new()
+                .until_event("done")
+                .capture(vec![capture::json("job_id", "job_id")])
+                .capture_event("progress", vec![capture::json("percent", "percent")])
+                .capture_event("done", vec![capture::json("result.code", "code")])
+                .count("event_count")
+                .expect_order(vec!["started", "progress", "done"]))
+            .expect(("percent", Value::I32(100)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new().until_json_eq("result.code", ":code"))
+            .expect(("event_count", Value::U64(5)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new().until_json_eq("result.error", Value::Null).timeout(Duration::from_secs(2)).count("event_count"))
+            .expect(("event_count", Value::U64(5)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new().max_events(2).count("event_count"))
+            .expect(("event_count", Value::U64(2)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 4);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_sse_failures() {
+    let addr = common::serve(|req| {
+        if req.request_line().starts_with("GET /open ") {
+            // no content-length, stream stays open until timeout
+            return "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\r\nevent: started\ndata: {}\n\n".to_string();
+        }
+        event_stream(EVENTS)
+    });
+    let url = format!("http://{}/events", addr);
+    let open_url = format!("http://{}/open", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new().expect_order(vec!["done", "started"]))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new().until_event("cancelled"))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .sse(Sse::new().max_events(10))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&open_url)
+            .sse(Sse::new().until_event("done").timeout(Duration::from_millis(300)))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&open_url)
+            .sse(Sse::new().max_events(1).timeout(Duration::from_secs(5)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![false, false, false, false, true]);
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use std::{net::SocketAddr, sync::{Arc, atomic::{AtomicUsize, Ordering}}};
+
+use anole::{engine::Engine, task::{http::HttpTaskBuilder, db::{mysql::MysqlTask, DatabaseTask, DBTask, DBClientOption}}, tls::{TlsOption, TlsVersion}};
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+const CA: &str = "tests/fixtures/tls/ca.pem";
+
+async fn tls_server() -> SocketAddr {
+    let cert = std::fs::read("tests/fixtures/tls/server.pem").unwrap();
+    let key = std::fs::read("tests/fixtures/tls/server.key").unwrap();
+    let identity = native_tls::Identity::from_pkcs8(&cert, &key).unwrap();
+    let acceptor = tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                if let Ok(mut stream) = acceptor.accept(stream).await {
+                    let mut buf = [0u8; 4096];
+                    let _ = stream.read(&mut buf).await;
+                    let _ = stream.write_all(common::json(r#"{"ok": true}"#).as_bytes()).await;
+                }
+            });
+        }
+    });
+    addr
+}
+
+async fn run(url: &str, tls: Option<TlsOption>) -> Vec<bool> {
+    let (reporter, recv) = common::reporter();
+    let mut builder = HttpTaskBuilder::new().url(url);
+    if let Some(tls) = tls {
+        builder = builder.tls(tls);
+    }
+    let r = Engine::new()
+        .with_reporter(reporter)
+        .with_http(builder.build())
+        .run().await;
+    if r.is_err() {
+        return vec![];
+    }
+    recv.try_iter().map(|r| r.is_success()).collect()
+}
+
+#[tokio::test]
+async fn test_custom_ca() {
+    let addr = tls_server().await;
+    let url = format!("https://localhost:{}/", addr.port());
+
+    assert_eq!(run(&url, None).await, Vec::<bool>::new());
+    assert_eq!(run(&url, Some(TlsOption::builder().root_cert(CA).min_version(TlsVersion::Tls1_2))).await, vec![true]);
+    assert_eq!(run(&url, Some(TlsOption::builder().accept_invalid_certs(true))).await, vec![true]);
+}
+
+#[tokio::test]
+async fn test_invalid_option() {
+    assert!(Engine::new()
+        .with_http(HttpTaskBuilder::new()
+            .url("https://localhost/")
+            .tls(TlsOption::builder().root_cert("tests/fixtures/tls/missing.pem"))
+            .build())
+        .run().await.is_err());
+
+    // client certificates are not supported by database drivers
+    assert!(Engine::new()
+        .with_mysql(MysqlTask::default()
+            .options(DBClientOption::builder()
+                .url("mysql://root@localhost/anole")
+                .tls(TlsOption::builder().client_pem("tests/fixtures/tls/server.pem", "tests/fixtures/tls/server.key"))))
+        .run().await.is_err());
+}
+
+/// MySQL 8.0.18 handshake packet offering TLS.
+const MYSQL_HANDSHAKE: &[u8] = b"\n8.0.18\x00\x19\x00\x00\x00\x114aB0c\x06g\x00\xff\xff\xff\x02\x00\xff\xc7\x15\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00tL\x03s\x0f[4\rl4. \x00caching_sha2_password\x00";
+
+/// Fake database server accepting TLS upgrade, returns the port and the number of completed TLS handshakes.
+async fn db_tls_server(mysql: bool) -> (u16, Arc<AtomicUsize>) {
+    let cert = std::fs::read("tests/fixtures/tls/server.pem").unwrap();
+    let key = std::fs::read("tests/fixtures/tls/server.key").unwrap();
+    let identity = native_tls::Identity::from_pkcs8(&cert, &key).unwrap();
+    let acceptor = tokio_native_tls::TlsAcceptor::from(native_tls::TlsAcceptor::new(identity).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handshakes = Arc::new(AtomicUsize::new(0));
+    let count = handshakes.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            let count = count.clone();
+            tokio::spawn(async move {
+                if mysql {
+                    // handshake packet, then SSLRequest packet of 32 bytes
+                    let mut packet = (MYSQL_HANDSHAKE.len() as u32).to_le_bytes();
+                    packet[3] = 0;
+                    let _ = stream.write_all(&packet).await;
+                    let _ = stream.write_all(MYSQL_HANDSHAKE).await;
+                    let mut ssl_request = [0u8; 36];
+                    let _ = stream.read_exact(&mut ssl_request).await;
+                } else {
+                    let mut ssl_request = [0u8; 8];
+                    let _ = stream.read_exact(&mut ssl_request).await;
+                    let _ = stream.write_all(b"S").await;
+                }
+                if acceptor.accept(stream).await.is_ok() {
+                    count.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+    (port, handshakes)
+}
+
+/// Connect with tls, returns whether TLS handshake completed, the fake server never completes the connection.
+async fn db_handshake(url: &str, tls: TlsOption, handshakes: &AtomicUsize) -> bool {
+    let before = handshakes.load(Ordering::SeqCst);
+    let r = Engine::new()
+        .with_database(DatabaseTask::default()
+            .options(DBClientOption::builder().url(url).max_connections(1).tls(tls))
+            .with_task(DBTask::new("SELECT 1")))
+        .run().await;
+    assert!(r.is_err());
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    handshakes.load(Ordering::SeqCst) > before
+}
+
+#[tokio::test]
+async fn test_db_ssl_mode() {
+    for (scheme, mysql) in [("postgres", false), ("mysql", true)] {
+        let (port, handshakes) = db_tls_server(mysql).await;
+        let url = format!("{}://anole@localhost:{}/anole", scheme, port);
+
+        // verify-full or verify-identity rejects the certificate of an unknown CA
+        assert!(!db_handshake(&url, TlsOption::builder(), &handshakes).await, "{}", scheme);
+        assert!(db_handshake(&url, TlsOption::builder().root_cert(CA), &handshakes).await, "{}", scheme);
+        // require skips verification
+        assert!(db_handshake(&url, TlsOption::builder().accept_invalid_certs(true), &handshakes).await, "{}", scheme);
+    }
+}
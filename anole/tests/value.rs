@@ -0,0 +1,134 @@
+mod common;
+
+#[cfg(test)]
+mod value {
+    use anole::value::Value;
+    use sqlx::types::{time, Decimal};
+
+    #[test]
+    fn test_json_serialize() {
+        let v = Value::object([
+            ("id", Value::from(1)),
+            ("name", Value::from("anole")),
+            ("tags", Value::array(["a", "b"])),
+        ]);
+        assert_eq!(serde_json::to_string(&v).unwrap(), r#"{"id":1,"name":"anole","tags":["a","b"]}"#);
+        assert_eq!(v.as_str(), r#"{"id":1,"name":"anole","tags":["a","b"]}"#);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let json = serde_json::json!({"code": 0, "data": {"list": [{"name": "kk"}, {"name": "uu"}]}});
+        let v = Value::from(&json);
+        assert_eq!(v.get_path("code"), Some(&Value::I32(0)));
+        assert_eq!(v.get_path("data.list|1.name"), Some(&Value::from("uu")));
+        assert!(v.get_path("data.list|2").is_none());
+        assert_eq!(serde_json::to_value(&v).unwrap(), json);
+
+        // structured values match their JSON text in expect checks only
+        let list = v.get_path("data.list").unwrap();
+        assert!(list.loose_eq(&Value::from(r#"[{"name": "kk"}, {"name": "uu"}]"#)));
+        assert!(Value::from(r#"{"list": [{"name": "kk"}, {"name": "uu"}]}"#).loose_eq(v.get_path("data").unwrap()));
+        assert!(!list.loose_eq(&Value::from(r#"[{"name": "kk"}]"#)));
+        assert!(!list.loose_eq(&Value::from("kk")));
+        assert_ne!(list, &Value::from(r#"[{"name":"kk"},{"name":"uu"}]"#));
+    }
+
+    #[test]
+    fn test_from_json_integers() {
+        let json = serde_json::json!({"small": -7, "big": 1_700_000_000_123i64, "huge": u64::MAX});
+        let v = Value::from(&json);
+        assert_eq!(v.get_path("small"), Some(&Value::I32(-7)));
+        assert_eq!(v.get_path("big"), Some(&Value::I64(1_700_000_000_123)));
+        assert_eq!(v.get_path("huge"), Some(&Value::U64(u64::MAX)));
+    }
+
+    #[test]
+    fn test_sql_types_eq() {
+        let price = Value::Decimal("12.50".parse::<Decimal>().unwrap());
+        assert_eq!(price, Value::Decimal("12.5".parse::<Decimal>().unwrap()));
+        assert!(price.loose_eq(&Value::from("12.5")));
+        assert!(Value::from("12.500").loose_eq(&price));
+        assert!(!price.loose_eq(&Value::from("12.51")));
+        assert_eq!(price.as_str(), "12.50");
+        // strict equality is transitive, strings never equal decimals
+        assert_ne!(price, Value::from("12.5"));
+        assert_ne!(Value::from("12.5"), Value::from("12.50"));
+        assert!(Value::array([Value::from("12.5")]).loose_eq(&Value::array([price.clone()])));
+
+        let created_at = Value::DateTime(time::PrimitiveDateTime::parse("2022-03-04 05:06:07", "%F %T").unwrap().assume_utc());
+        assert!(created_at.loose_eq(&Value::from("2022-03-04T05:06:07Z")));
+        assert!(created_at.loose_eq(&Value::from("2022-03-04T13:06:07+08:00")));
+        assert!(created_at.loose_eq(&Value::from("2022-03-04 05:06:07")));
+        assert!(!created_at.loose_eq(&Value::from("2022-03-04T05:06:07.5Z")));
+        assert_ne!(created_at, Value::from("2022-03-04T05:06:07Z"));
+        assert_eq!(created_at.as_str(), "2022-03-04T05:06:07Z");
+        assert_eq!(serde_json::to_string(&created_at).unwrap(), r#""2022-03-04T05:06:07Z""#);
+
+        let bytes = Value::Bytes(vec![0xca, 0xfe]);
+        assert_eq!(bytes.as_str(), "cafe");
+        assert_eq!(serde_json::to_string(&Value::array([bytes.clone()])).unwrap(), r#"["cafe"]"#);
+        assert_ne!(bytes, Value::from("cafe"));
+        assert_eq!(Value::Null, Value::Null);
+        assert_ne!(Value::Null, Value::from("null"));
+    }
+}
+
+#[tokio::test]
+async fn test_json_body_round_trip() {
+    use anole::{engine::Engine, task::http::{HttpTaskBuilder, Method}, value::Value, capture};
+
+    let addr = common::serve(|req| {
+        if req.request_line().starts_with("GET /user") {
+            return common::json(r#"{"user": {"id": 42, "parent": null, "roles": ["admin", "dev"]}}"#);
+        }
+        let body: serde_json::Value = serde_json::from_str(&req.body_str()).unwrap_or_default();
+        match req.header("content-type") {
+            // nested nulls must be sent back as JSON null, not the string "null"
+            Some(ct) if ct == "application/json" && body["owner"]["parent"].is_null() && body["owner"].get("parent").is_some() => {
+                common::json(&req.body_str())
+            }
+            _ => common::response("400 Bad Request", &[], ""),
+        }
+    });
+    let user_url = format!("http://{}/user", addr);
+    let echo_url = format!("http://{}/echo", addr);
+    let user = Value::object([("id", Value::I32(42)), ("parent", Value::Null), ("roles", Value::array(["admin", "dev"]))]);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&user_url)
+            .capture(vec![capture::json("user", "user")])
+            .expect(("user", user.clone()))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&echo_url)
+            .method(Method::Post)
+            .json(Value::object([
+                ("owner", Value::from(":user")),
+                ("role", Value::from(":user.roles|1")),
+                ("tags", Value::array(["a", ":user.id", ":missing"])),
+            ]))
+            .capture(vec![capture::json("owner", "owner"), capture::json("role", "role"), capture::json("tags", "tags")])
+            .expect(("owner", user))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&user_url)
+            .expect(("tags", Value::array([Value::from("a"), Value::I32(42), Value::from(":missing")])))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&user_url)
+            .expect(("role", Value::from("dev")))
+            .build())
+        // structured captures still match their JSON text
+        .with_http(HttpTaskBuilder::new()
+            .url(&user_url)
+            .expect(("owner", Value::from(r#"{"id": 42, "parent": null, "roles": ["admin", "dev"]}"#)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 5);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use std::net::SocketAddr;
+
+use anole::{engine::Engine, task::http::{HttpTaskBuilder, Method, session::HttpVersion}, capture, tls::TlsOption, value::Value};
+use tokio::net::TcpListener;
+
+/// h2c server answering method name.
+async fn h2_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(serve_h2(stream));
+        }
+    });
+    addr
+}
+
+/// TLS server offering `h2` by ALPN, answering method name over HTTP/2 only.
+async fn h2_tls_server() -> SocketAddr {
+    let cert = std::fs::read("tests/fixtures/tls/server.pem").unwrap();
+    let key = std::fs::read("tests/fixtures/tls/server.key").unwrap();
+    let identity = native_tls::Identity::from_pkcs8(&cert, &key).unwrap();
+    let acceptor = native_tls::TlsAcceptor::builder(identity).accept_alpn(&["h2"]).build().unwrap();
+    let acceptor = tokio_native_tls::TlsAcceptor::from(acceptor);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                if let Ok(stream) = acceptor.accept(stream).await {
+                    serve_h2(stream).await;
+                }
+            });
+        }
+    });
+    addr
+}
+
+async fn serve_h2<S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin>(stream: S) {
+    let mut conn = match h2::server::handshake(stream).await {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+    while let Some(Ok((req, mut respond))) = conn.accept().await {
+        let body = format!(r#"{{"method": "{}"}}"#, req.method());
+        let rsp = http::Response::builder().header("content-type", "application/json").body(()).unwrap();
+        let mut send = respond.send_response(rsp, false).unwrap();
+        send.send_data(body.into(), true).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_http2_alpn() {
+    let addr = h2_tls_server().await;
+    let url = format!("https://localhost:{}/", addr.port());
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .tls(TlsOption::builder().root_cert("tests/fixtures/tls/ca.pem"))
+            .capture(vec![capture::version("version"), capture::json("method", "method")])
+            .expect(("version", Value::from("HTTP/2.0")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 1);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_http2_prior_knowledge() {
+    let addr = h2_server().await;
+    let url = format!("http://{}/", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .method(Method::custom("PURGE").unwrap())
+            .http_version(HttpVersion::Http2PriorKnowledge)
+            .capture(vec![capture::version("version"), capture::json("method", "method")])
+            .expect(("version", Value::from("HTTP/2.0")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .method(Method::Options)
+            .http_version(HttpVersion::Http2PriorKnowledge)
+            .capture(vec![capture::json("method", "method")])
+            .expect(("method", Value::from("OPTIONS")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[tokio::test]
+async fn test_http1() {
+    let addr = common::serve(|req| {
+        let method = req.request_line().split(' ').next().unwrap_or_default().to_string();
+        common::json(&format!(r#"{{"method": "{}"}}"#, method))
+    });
+    let url = format!("http://{}/", addr);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .method(Method::custom("REPORT").unwrap())
+            .http_version(HttpVersion::Http1Only)
+            .capture(vec![capture::version("version"), capture::json("method", "method")])
+            .expect(("method", Value::from("REPORT")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .capture(vec![capture::version("version")])
+            .expect(("version", Value::from("HTTP/1.1")))
+            .build())
+        .with_http(HttpTaskBuilder::new()
+            .url(&url)
+            .method(Method::Trace)
+            .capture(vec![capture::json("method", "method")])
+            .expect(("method", Value::from("TRACE")))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().collect::<Vec<_>>();
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|r| r.is_success()), "{:?}", items);
+}
+
+#[test]
+fn test_method() {
+    assert_eq!(reqwest::Method::from(Method::Get), reqwest::Method::GET);
+    assert_eq!(reqwest::Method::from(&Method::Trace), reqwest::Method::TRACE);
+    assert_eq!(reqwest::Method::from(Method::Connect), reqwest::Method::CONNECT);
+    assert_eq!(reqwest::Method::from(Method::custom("PURGE").unwrap()).as_str(), "PURGE");
+    assert!(Method::custom("BAD VERB").is_err());
+    assert!(Method::custom("").is_err());
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use std::{net::SocketAddr, time::Duration};
+
+use anole::{engine::Engine, task::{http::HttpTaskBuilder, websocket::{WebSocketTaskBuilder, Wait, Frame}}, capture, value::Value};
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::{Message, handshake::server::{Request, Response}};
+
+/// Notification server, pushes a notification for user after subscribing.
+#[allow(clippy::result_large_err)]
+async fn ws_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut path = String::new();
+                let mut token = String::new();
+                let mut ws = tokio_tungstenite::accept_hdr_async(stream, |req: &Request, rsp: Response| {
+                    path = req.uri().path().to_string();
+                    token = req.headers().get("x-token").and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+                    Ok(rsp)
+                }).await.unwrap();
+                while let Some(Ok(msg)) = ws.next().await {
+                    let text = match msg {
+                        Message::Text(t) => t,
+                        Message::Binary(b) => {
+                            ws.send(Message::Binary(b)).await.unwrap();
+                            continue;
+                        }
+                        _ => continue,
+                    };
+                    let req: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    if req["action"] == "subscribe" {
+                        ws.send(Message::Text(r#"{"type": "ack"}"#.to_string())).await.unwrap();
+                        ws.send(Message::Text("heartbeat".to_string())).await.unwrap();
+                        let n = serde_json::json!({"type": "notification", "data": {"path": path, "token": token, "id": 7}, "deleted_at": null});
+                        ws.send(Message::Text(n.to_string())).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+    addr
+}
+
+#[tokio::test]
+async fn test_websocket() {
+    let http = common::serve(|_| common::json(r#"{"user_id": 42, "token": "t1"}"#));
+    let ws = ws_server().await;
+    let login = format!("http://{}/login", http);
+    let notify = format!("ws://{}/notify/:user_id", ws);
+    let (reporter, recv) = common::reporter();
+    Engine::new()
+        .with_reporter(reporter)
+        .with_http(HttpTaskBuilder::new()
+            .url(&login)
+            .capture(vec![capture::json("user_id", "user_id"), capture::json("token", "token")])
+            .build())
+        .with_websocket(WebSocketTaskBuilder::new()
+            .url(&notify)
+            .header(("x-token", Value::from(":token")))
+            .send_json(Value::object([("action", "subscribe"), ("user", ":user_id")]))
+            .wait(Wait::json_eq("type", "notification")
+                .capture(vec![capture::json("data.id", "notification_id"), capture::json("data.path", "path"), capture::json("data.token", "ws_token")]))
+            .send_binary(vec![1u8, 2, 3])
+            .wait(Wait::matching(|f| matches!(f, Frame::Binary(b) if *b == [1u8, 2, 3])))
+            .expect(("ws_token", Value::from("t1")))
+            .build())
+        .with_websocket(WebSocketTaskBuilder::new()
+            .url(&notify)
+            .send_json(Value::object([("action", "subscribe")]))
+            .wait(Wait::text_contains("never").timeout(Duration::from_millis(200)))
+            .build())
+        .with_websocket(WebSocketTaskBuilder::new()
+            .url(&notify)
+            .send_json(Value::object([("action", "subscribe")]))
+            .wait(Wait::json_eq("deleted_at", Value::Null)
+                .timeout(Duration::from_secs(2))
+                .capture(vec![capture::json("data.id", "live_id")]))
+            .expect(("live_id", Value::I32(7)))
+            .build())
+        .run().await.unwrap();
+
+    let items = recv.try_iter().map(|r| r.is_success()).collect::<Vec<_>>();
+    assert_eq!(items, vec![true, true, false, true]);
+}